@@ -3,12 +3,47 @@
 
 use std::collections::{HashMap, VecDeque};
 
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+use crate::error::BetError;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Task {
     pub id: String,
     pub name: String,
     pub duration: u32,
     pub dependencies: Vec<String>,
+    // Units of shared capacity held while running; 0 unless set, also when loading older task JSON
+    #[serde(default)]
+    pub resources: u32,
+}
+
+impl Task {
+    pub fn new(id: &str, name: &str, duration: u32, dependencies: &[&str]) -> Self {
+        Task {
+            id: id.to_string(),
+            name: name.to_string(),
+            duration,
+            dependencies: dependencies.iter().map(|dep| dep.to_string()).collect(),
+            resources: 0,
+        }
+    }
+
+    pub fn with_resources(mut self, resources: u32) -> Self {
+        self.resources = resources;
+        self
+    }
+}
+
+// Forward/backward pass timings for one task
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskSchedule {
+    pub id: String,
+    pub earliest_start: u32,
+    pub earliest_finish: u32,
+    pub latest_start: u32,
+    pub latest_finish: u32,
+    pub slack: u32,
 }
 
 #[derive(Debug)]
@@ -33,7 +68,22 @@ impl OctoTree {
         self.tasks.insert(task.id.clone(), task);
     }
 
-    pub fn topological_sort(&self) -> Result<Vec<String>, String> {
+    pub fn contains_task(&self, id: &str) -> bool {
+        self.tasks.contains_key(id)
+    }
+
+    pub fn get_task(&self, id: &str) -> Option<&Task> {
+        self.tasks.get(id)
+    }
+
+    pub fn topological_sort(&self) -> Result<Vec<String>, BetError> {
+        // Dependencies on tasks that were never added can't be scheduled
+        for task in self.tasks.values() {
+            if let Some(dep) = task.dependencies.iter().find(|dep| !self.tasks.contains_key(*dep)) {
+                return Err(BetError::UnknownTask(dep.clone()));
+            }
+        }
+
         let mut in_degree: HashMap<String, usize> = HashMap::new();
         let mut adj_list: HashMap<String, Vec<String>> = HashMap::new();
 
@@ -70,13 +120,20 @@ impl OctoTree {
         }
 
         if result.len() != self.tasks.len() {
-            Err("Cycle detected in DAG".to_string())
+            // Whatever never reached in-degree zero is on (or behind) a cycle
+            let mut stuck: Vec<String> = in_degree
+                .into_iter()
+                .filter(|(_, degree)| *degree > 0)
+                .map(|(id, _)| id)
+                .collect();
+            stuck.sort();
+            Err(BetError::Cycle(stuck))
         } else {
             Ok(result)
         }
     }
 
-    pub fn critical_path(&self) -> Result<(Vec<String>, u32), String> {
+    pub fn critical_path(&self) -> Result<(Vec<String>, u32), BetError> {
         let topo_order = self.topological_sort()?;
         let mut earliest_start: HashMap<String, u32> = HashMap::new();
 
@@ -101,6 +158,196 @@ impl OctoTree {
         Ok((critical_tasks, max_time))
     }
 
+    // Earliest/latest start and finish for every task, in topological order.
+    // Tasks with zero slack form the critical chain
+    pub fn schedule_report(&self) -> Result<Vec<TaskSchedule>, BetError> {
+        let order = self.topological_sort()?;
+
+        // Forward pass: a task starts once its slowest dependency finishes
+        let mut earliest_finish: HashMap<&str, u32> = HashMap::new();
+        let mut earliest_start: HashMap<&str, u32> = HashMap::new();
+        for task_id in &order {
+            let task = &self.tasks[task_id];
+            let start = task.dependencies.iter()
+                .map(|dep| earliest_finish[dep.as_str()])
+                .max()
+                .unwrap_or(0);
+            earliest_start.insert(task_id, start);
+            earliest_finish.insert(task_id, start + task.duration);
+        }
+        let project_end = earliest_finish.values().copied().max().unwrap_or(0);
+
+        // Backward pass: a task must finish before its earliest-starting dependent has to start
+        let mut latest_start: HashMap<&str, u32> = HashMap::new();
+        let mut latest_finish: HashMap<&str, u32> = HashMap::new();
+        for task_id in order.iter().rev() {
+            let finish = self.tasks.values()
+                .filter(|t| t.dependencies.contains(task_id))
+                .map(|t| latest_start[t.id.as_str()])
+                .min()
+                .unwrap_or(project_end);
+            latest_finish.insert(task_id, finish);
+            latest_start.insert(task_id, finish - self.tasks[task_id].duration);
+        }
+
+        Ok(order
+            .iter()
+            .map(|id| {
+                let id = id.as_str();
+                TaskSchedule {
+                    id: id.to_string(),
+                    earliest_start: earliest_start[id],
+                    earliest_finish: earliest_finish[id],
+                    latest_start: latest_start[id],
+                    latest_finish: latest_finish[id],
+                    slack: latest_start[id] - earliest_start[id],
+                }
+            })
+            .collect())
+    }
+
+    // Greedy list scheduler: each task, in dependency order, starts at the first time
+    // after its dependencies finish where the running tasks leave enough resources free
+    pub fn schedule(&self, capacity: u32) -> Result<Vec<(String, u32)>, BetError> {
+        let order = self.topological_sort()?;
+        // (start, finish, resources) of everything placed so far
+        let mut placed: Vec<(u32, u32, u32)> = Vec::new();
+        let mut finish_times: HashMap<&str, u32> = HashMap::new();
+        let mut schedule = Vec::with_capacity(order.len());
+
+        for task_id in &order {
+            let task = &self.tasks[task_id];
+            if task.resources > capacity {
+                return Err(BetError::Capacity(format!(
+                    "{} needs {} but capacity is {}",
+                    task.id, task.resources, capacity
+                )));
+            }
+
+            let ready = task.dependencies.iter()
+                .map(|dep| finish_times[dep.as_str()])
+                .max()
+                .unwrap_or(0);
+
+            // Usage only changes at placed start/finish times, so those are the candidates
+            let mut candidates: Vec<u32> = placed.iter()
+                .map(|&(_, finish, _)| finish)
+                .filter(|&finish| finish > ready)
+                .collect();
+            candidates.push(ready);
+            candidates.sort();
+
+            let fits = |start: u32| {
+                let end = start + task.duration;
+                let mut checkpoints = vec![start];
+                checkpoints.extend(placed.iter().map(|&(s, _, _)| s).filter(|&s| s > start && s < end));
+                checkpoints.iter().all(|&t| {
+                    let used: u32 = placed.iter()
+                        .filter(|&&(s, f, _)| s <= t && t < f)
+                        .map(|&(_, _, r)| r)
+                        .sum();
+                    used + task.resources <= capacity
+                })
+            };
+            let start = candidates.into_iter().find(|&t| fits(t)).unwrap_or(ready);
+
+            placed.push((start, start + task.duration, task.resources));
+            finish_times.insert(task_id.as_str(), start + task.duration);
+            schedule.push((task_id.clone(), start));
+        }
+
+        schedule.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(schedule)
+    }
+
+    // Tasks grouped by depth: roots at level 0, every other task one level below its
+    // deepest dependency. Ids within a level are sorted so the layout is stable
+    pub fn execution_levels(&self) -> Result<Vec<Vec<String>>, BetError> {
+        let order = self.topological_sort()?;
+        let mut level_of: HashMap<&str, usize> = HashMap::new();
+        let mut levels: Vec<Vec<String>> = Vec::new();
+
+        for task_id in &order {
+            let task = &self.tasks[task_id];
+            let level = task
+                .dependencies
+                .iter()
+                .map(|dep| level_of[dep.as_str()] + 1)
+                .max()
+                .unwrap_or(0);
+            level_of.insert(task_id.as_str(), level);
+            if levels.len() <= level {
+                levels.resize(level + 1, Vec::new());
+            }
+            levels[level].push(task_id.clone());
+        }
+
+        for level in &mut levels {
+            level.sort();
+        }
+        Ok(levels)
+    }
+
+    // Every task that transitively depends on `id`, sorted
+    pub fn descendants(&self, id: &str) -> Result<Vec<String>, BetError> {
+        self.reachable(id, |task, current| task.dependencies.iter().any(|dep| dep == current))
+    }
+
+    // Every task `id` transitively depends on, sorted
+    pub fn ancestors(&self, id: &str) -> Result<Vec<String>, BetError> {
+        self.reachable(id, |task, current| self.tasks[current].dependencies.contains(&task.id))
+    }
+
+    // Breadth-first walk from `id`, following tasks for which `linked(task, current)` holds
+    fn reachable(&self, id: &str, linked: impl Fn(&Task, &str) -> bool) -> Result<Vec<String>, BetError> {
+        if !self.tasks.contains_key(id) {
+            return Err(BetError::UnknownTask(id.to_string()));
+        }
+        let mut found: Vec<String> = Vec::new();
+        let mut queue = VecDeque::from([id.to_string()]);
+
+        while let Some(current) = queue.pop_front() {
+            for task in self.tasks.values() {
+                if task.id != id && !found.contains(&task.id) && linked(task, &current) {
+                    found.push(task.id.clone());
+                    queue.push_back(task.id.clone());
+                }
+            }
+        }
+
+        found.sort();
+        Ok(found)
+    }
+
+    // Indented text layout, roots first and leaves last, e.g.
+    //   A
+    //   ├─ B ← A
+    //   └─ C ← A
+    pub fn display_tree(&self) -> Result<String, BetError> {
+        let levels = self.execution_levels()?;
+        let mut lines = Vec::new();
+
+        for (depth, level) in levels.iter().enumerate() {
+            for (i, task_id) in level.iter().enumerate() {
+                if depth == 0 {
+                    lines.push(format!("  {}", task_id));
+                    continue;
+                }
+                let branch = if i + 1 == level.len() { "└─" } else { "├─" };
+                let mut deps = self.tasks[task_id].dependencies.clone();
+                deps.sort();
+                lines.push(format!(
+                    "  {}{} {} ← {}",
+                    "   ".repeat(depth - 1),
+                    branch,
+                    task_id,
+                    deps.join(", ")
+                ));
+            }
+        }
+        Ok(lines.join("\n"))
+    }
+
     pub fn display(&self) -> String {
         format!("OCTOTREÉ: {} tasks", self.tasks.len())
     }
@@ -113,18 +360,8 @@ mod tests {
     #[test]
     fn test_topological_sort() {
         let mut tree = OctoTree::new();
-        tree.add_task(Task {
-            id: "A".to_string(),
-            name: "Task A".to_string(),
-            duration: 5,
-            dependencies: vec![],
-        });
-        tree.add_task(Task {
-            id: "B".to_string(),
-            name: "Task B".to_string(),
-            duration: 3,
-            dependencies: vec!["A".to_string()],
-        });
+        tree.add_task(Task::new("A", "Task A", 5, &[]));
+        tree.add_task(Task::new("B", "Task B", 3, &["A"]));
 
         let result = tree.topological_sort().unwrap();
         assert_eq!(result, vec!["A", "B"]);
@@ -139,42 +376,17 @@ mod tests {
     #[test]
     fn test_octo_tree_add_task() {
         let mut tree = OctoTree::new();
-        tree.add_task(Task {
-            id: "A".to_string(),
-            name: "Task A".to_string(),
-            duration: 5,
-            dependencies: vec![],
-        });
+        tree.add_task(Task::new("A", "Task A", 5, &[]));
         assert_eq!(tree.tasks.len(), 1);
     }
 
     #[test]
     fn test_topological_sort_multiple_tasks() {
         let mut tree = OctoTree::new();
-        tree.add_task(Task {
-            id: "A".to_string(),
-            name: "Task A".to_string(),
-            duration: 5,
-            dependencies: vec![],
-        });
-        tree.add_task(Task {
-            id: "B".to_string(),
-            name: "Task B".to_string(),
-            duration: 3,
-            dependencies: vec!["A".to_string()],
-        });
-        tree.add_task(Task {
-            id: "C".to_string(),
-            name: "Task C".to_string(),
-            duration: 2,
-            dependencies: vec!["A".to_string()],
-        });
-        tree.add_task(Task {
-            id: "D".to_string(),
-            name: "Task D".to_string(),
-            duration: 1,
-            dependencies: vec!["B".to_string(), "C".to_string()],
-        });
+        tree.add_task(Task::new("A", "Task A", 5, &[]));
+        tree.add_task(Task::new("B", "Task B", 3, &["A"]));
+        tree.add_task(Task::new("C", "Task C", 2, &["A"]));
+        tree.add_task(Task::new("D", "Task D", 1, &["B", "C"]));
 
         let result = tree.topological_sort().unwrap();
         assert_eq!(result.len(), 4);
@@ -185,39 +397,43 @@ mod tests {
     #[test]
     fn test_topological_sort_cycle_detection() {
         let mut tree = OctoTree::new();
-        tree.add_task(Task {
-            id: "A".to_string(),
-            name: "Task A".to_string(),
-            duration: 5,
-            dependencies: vec!["B".to_string()],
-        });
-        tree.add_task(Task {
-            id: "B".to_string(),
-            name: "Task B".to_string(),
-            duration: 3,
-            dependencies: vec!["A".to_string()],
-        });
+        tree.add_task(Task::new("A", "Task A", 5, &["B"]));
+        tree.add_task(Task::new("B", "Task B", 3, &["A"]));
 
         let result = tree.topological_sort();
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Cycle"));
+        assert!(result.unwrap_err().to_string().contains("Cycle"));
+    }
+
+    #[test]
+    fn test_topological_sort_cycle_variant() {
+        let mut tree = OctoTree::new();
+        for (id, dep) in [("A", "B"), ("B", "A")] {
+            tree.add_task(Task::new(id, &format!("Task {}", id), 1, &[dep]));
+        }
+
+        match tree.topological_sort() {
+            Err(BetError::Cycle(tasks)) => assert_eq!(tasks, vec!["A", "B"]),
+            other => panic!("expected cycle, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_topological_sort_unknown_dependency() {
+        let mut tree = OctoTree::new();
+        tree.add_task(Task::new("A", "Task A", 1, &["missing"]));
+
+        assert_eq!(
+            tree.topological_sort(),
+            Err(BetError::UnknownTask("missing".to_string()))
+        );
     }
 
     #[test]
     fn test_critical_path() {
         let mut tree = OctoTree::new();
-        tree.add_task(Task {
-            id: "A".to_string(),
-            name: "Task A".to_string(),
-            duration: 5,
-            dependencies: vec![],
-        });
-        tree.add_task(Task {
-            id: "B".to_string(),
-            name: "Task B".to_string(),
-            duration: 3,
-            dependencies: vec!["A".to_string()],
-        });
+        tree.add_task(Task::new("A", "Task A", 5, &[]));
+        tree.add_task(Task::new("B", "Task B", 3, &["A"]));
 
         let result = tree.critical_path();
         assert!(result.is_ok());
@@ -226,13 +442,124 @@ mod tests {
     #[test]
     fn test_display() {
         let mut tree = OctoTree::new();
-        tree.add_task(Task {
-            id: "A".to_string(),
-            name: "Task A".to_string(),
-            duration: 5,
-            dependencies: vec![],
-        });
+        tree.add_task(Task::new("A", "Task A", 5, &[]));
         let display = tree.display();
         assert!(display.contains("1 tasks"));
     }
+
+    fn resource_task(id: &str, duration: u32, resources: u32, deps: &[&str]) -> Task {
+        Task::new(id, &format!("Task {}", id), duration, deps).with_resources(resources)
+    }
+
+    #[test]
+    fn test_task_json_without_resources_defaults_to_zero() {
+        let task: Task =
+            serde_json::from_str(r#"{"id":"A","name":"Task A","duration":5,"dependencies":["B"]}"#).unwrap();
+        assert_eq!(task, Task::new("A", "Task A", 5, &["B"]));
+        assert_eq!(task.resources, 0);
+    }
+
+    #[test]
+    fn test_schedule_report_slack_on_parallel_branch() {
+        let mut tree = OctoTree::new();
+        tree.add_task(resource_task("A", 2, 0, &[]));
+        tree.add_task(resource_task("B", 5, 0, &["A"]));
+        tree.add_task(resource_task("C", 1, 0, &["A"]));
+        tree.add_task(resource_task("D", 3, 0, &["B", "C"]));
+
+        let report = tree.schedule_report().unwrap();
+        assert_eq!(report.first().map(|t| t.id.as_str()), Some("A"));
+        assert_eq!(report.last().map(|t| t.id.as_str()), Some("D"));
+
+        let c = report.iter().find(|t| t.id == "C").unwrap();
+        assert_eq!((c.earliest_start, c.earliest_finish), (2, 3));
+        assert_eq!((c.latest_start, c.latest_finish), (6, 7));
+        assert_eq!(c.slack, 4);
+
+        let critical: Vec<&str> = report.iter().filter(|t| t.slack == 0).map(|t| t.id.as_str()).collect();
+        assert_eq!(critical, vec!["A", "B", "D"]);
+    }
+
+    #[test]
+    fn test_schedule_serializes_tasks_over_capacity() {
+        let mut tree = OctoTree::new();
+        tree.add_task(resource_task("A", 3, 2, &[]));
+        tree.add_task(resource_task("B", 3, 2, &[]));
+
+        let schedule = tree.schedule(3).unwrap();
+        let starts: Vec<u32> = schedule.iter().map(|(_, start)| *start).collect();
+        assert_eq!(starts, vec![0, 3]);
+
+        // With enough room they run side by side
+        let parallel = tree.schedule(4).unwrap();
+        assert!(parallel.iter().all(|(_, start)| *start == 0));
+    }
+
+    #[test]
+    fn test_schedule_respects_dependencies() {
+        let mut tree = OctoTree::new();
+        tree.add_task(resource_task("A", 2, 1, &[]));
+        tree.add_task(resource_task("B", 4, 1, &["A"]));
+        tree.add_task(resource_task("C", 1, 0, &["B"]));
+
+        let schedule = tree.schedule(1).unwrap();
+        assert_eq!(
+            schedule,
+            vec![("A".to_string(), 0), ("B".to_string(), 2), ("C".to_string(), 6)]
+        );
+    }
+
+    #[test]
+    fn test_schedule_task_larger_than_capacity() {
+        let mut tree = OctoTree::new();
+        tree.add_task(resource_task("A", 1, 5, &[]));
+        assert!(matches!(tree.schedule(4), Err(BetError::Capacity(_))));
+    }
+
+    #[test]
+    fn test_display_tree_diamond() {
+        let mut tree = OctoTree::new();
+        tree.add_task(resource_task("A", 1, 0, &[]));
+        tree.add_task(resource_task("B", 1, 0, &["A"]));
+        tree.add_task(resource_task("C", 1, 0, &["A"]));
+        tree.add_task(resource_task("D", 1, 0, &["B", "C"]));
+
+        assert_eq!(
+            tree.execution_levels().unwrap(),
+            vec![vec!["A".to_string()], vec!["B".to_string(), "C".to_string()], vec!["D".to_string()]]
+        );
+
+        let layout = tree.display_tree().unwrap();
+        for id in ["A", "B", "C", "D"] {
+            assert!(layout.contains(id));
+        }
+        assert!(layout.contains("├─ B ← A"));
+        assert!(layout.contains("└─ C ← A"));
+        assert!(layout.contains("└─ D ← B, C"));
+        assert!(layout.starts_with("  A"));
+    }
+
+    #[test]
+    fn test_display_tree_cycle() {
+        let mut tree = OctoTree::new();
+        tree.add_task(resource_task("A", 1, 0, &["B"]));
+        tree.add_task(resource_task("B", 1, 0, &["A"]));
+        assert!(matches!(tree.display_tree(), Err(BetError::Cycle(_))));
+    }
+
+    #[test]
+    fn test_descendants_and_ancestors() {
+        let mut tree = OctoTree::new();
+        tree.add_task(resource_task("A", 1, 0, &[]));
+        tree.add_task(resource_task("B", 1, 0, &["A"]));
+        tree.add_task(resource_task("C", 1, 0, &["A"]));
+        tree.add_task(resource_task("D", 1, 0, &["B", "C"]));
+
+        assert_eq!(tree.descendants("A").unwrap(), vec!["B", "C", "D"]);
+        assert_eq!(tree.descendants("B").unwrap(), vec!["D"]);
+        assert!(tree.descendants("D").unwrap().is_empty());
+        assert_eq!(tree.ancestors("D").unwrap(), vec!["A", "B", "C"]);
+        assert!(tree.ancestors("A").unwrap().is_empty());
+        assert_eq!(tree.ancestors("nope"), Err(BetError::UnknownTask("nope".to_string())));
+    }
 }
@@ -0,0 +1,135 @@
+// Audit Log
+// Append-only JSON-lines record of every order and position change
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::trading::{Order, OrderSide, OrderType, Trade};
+use crate::trading_models::Position;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Event {
+    OrderSubmitted {
+        symbol: String,
+        side: OrderSide,
+        order_type: OrderType,
+        quantity: f64,
+        timestamp: u64,
+    },
+    TradeExecuted(Trade),
+    PositionChanged {
+        symbol: String,
+        quantity: f64,
+        avg_price: f64,
+        timestamp: u64,
+    },
+}
+
+impl Event {
+    pub fn order_submitted(order: &Order, timestamp: u64) -> Self {
+        Event::OrderSubmitted {
+            symbol: order.symbol.clone(),
+            side: order.side,
+            order_type: order.order_type.clone(),
+            quantity: order.quantity,
+            timestamp,
+        }
+    }
+
+    pub fn position_changed(position: &Position, timestamp: u64) -> Self {
+        Event::PositionChanged {
+            symbol: position.symbol.clone(),
+            quantity: position.quantity,
+            avg_price: position.avg_price,
+            timestamp,
+        }
+    }
+}
+
+// Append one event as a single JSON line, creating the file if needed
+pub fn log_event(path: &str, event: &Event) -> Result<(), String> {
+    let mut line = serde_json::to_string(event).map_err(|e| format!("Failed to serialize event: {}", e))?;
+    line.push('\n');
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    // One write per line so a crash can at worst truncate the final entry
+    file.write_all(line.as_bytes())
+        .and_then(|_| file.flush())
+        .map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+pub fn read_events(path: &str) -> Result<Vec<Event>, String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| format!("Failed to parse event on line {}: {}", i + 1, e))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_log(name: &str) -> String {
+        let path = std::env::temp_dir().join(name);
+        let _ = fs::remove_file(&path);
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn test_log_and_read_events() {
+        let path = temp_log("audit_log_round_trip.jsonl");
+        let order = Order::new("MRNA", OrderSide::Buy, OrderType::Limit(50.0), 10.0);
+        let trade = Trade {
+            id: 1,
+            symbol: "MRNA".to_string(),
+            side: OrderSide::Buy,
+            quantity: 10.0,
+            price: 49.5,
+            timestamp: 2,
+            signal_id: None,
+        };
+        let position = Position {
+            symbol: "MRNA".to_string(),
+            quantity: 10.0,
+            avg_price: 49.5,
+            current_price: 49.5,
+        };
+        let events = vec![
+            Event::order_submitted(&order, 1),
+            Event::TradeExecuted(trade),
+            Event::position_changed(&position, 2),
+        ];
+
+        for event in &events {
+            log_event(&path, event).unwrap();
+        }
+        let read_back = read_events(&path).unwrap();
+        let lines = fs::read_to_string(&path).unwrap().lines().count();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(lines, 3);
+        assert_eq!(read_back, events);
+    }
+
+    #[test]
+    fn test_read_events_errors() {
+        let path = temp_log("audit_log_corrupt.jsonl");
+        fs::write(&path, "{\"not\": \"an event\"}\n").unwrap();
+        let err = read_events(&path).unwrap_err();
+        let _ = fs::remove_file(&path);
+        assert!(err.contains("line 1"));
+
+        assert!(read_events(&temp_log("audit_log_missing.jsonl")).is_err());
+    }
+}
@@ -0,0 +1,263 @@
+// Backtesting
+// Runs a strategy over a price series: signal -> order -> fill -> P&L
+
+use crate::market_data::OHLCV;
+use crate::pnl::{PnLCalculator, PnLReport};
+use crate::signals::SignalType;
+use crate::trading::{Order, OrderSide, OrderType};
+use crate::trading_models::Position;
+
+#[derive(Debug, Clone)]
+pub struct BacktestResult {
+    pub report: PnLReport,
+    pub equity_curve: Vec<f64>,
+    pub position: Position,
+    pub orders_filled: usize,
+}
+
+// Where in a bar a market order gets filled
+pub trait FillModel {
+    fn fill_price(&self, side: &OrderSide, bar: &OHLCV) -> f64;
+
+    // True if a signal on one bar should fill against the following bar
+    fn fills_next_bar(&self) -> bool {
+        false
+    }
+}
+
+// Fill at the close of the signal bar
+pub struct CloseFill;
+
+impl FillModel for CloseFill {
+    fn fill_price(&self, _side: &OrderSide, bar: &OHLCV) -> f64 {
+        bar.close
+    }
+}
+
+// Fill at the open of the bar after the signal; a signal on the last bar never fills
+pub struct NextOpenFill;
+
+impl FillModel for NextOpenFill {
+    fn fill_price(&self, _side: &OrderSide, bar: &OHLCV) -> f64 {
+        bar.open
+    }
+
+    fn fills_next_bar(&self) -> bool {
+        true
+    }
+}
+
+// Pessimistic: buys pay the high, sells get the low
+pub struct WorstCaseFill;
+
+impl FillModel for WorstCaseFill {
+    fn fill_price(&self, side: &OrderSide, bar: &OHLCV) -> f64 {
+        match side {
+            OrderSide::Buy => bar.high,
+            OrderSide::Sell => bar.low,
+        }
+    }
+}
+
+pub struct Backtester {
+    pub symbol: String,
+    pub initial_capital: f64,
+    pub trade_quantity: f64,
+    pub fill_model: Box<dyn FillModel>,
+}
+
+impl Backtester {
+    pub fn new(symbol: &str, initial_capital: f64, trade_quantity: f64) -> Self {
+        Backtester {
+            symbol: symbol.to_string(),
+            initial_capital,
+            trade_quantity,
+            fill_model: Box::new(CloseFill),
+        }
+    }
+
+    pub fn with_fill_model(mut self, fill_model: Box<dyn FillModel>) -> Self {
+        self.fill_model = fill_model;
+        self
+    }
+
+    // Close-only series: each price is treated as a flat bar
+    pub fn run<F>(&self, prices: &[f64], strategy: F) -> BacktestResult
+    where
+        F: FnMut(&[f64]) -> SignalType,
+    {
+        let bars: Vec<OHLCV> = prices
+            .iter()
+            .map(|&price| OHLCV {
+                open: price,
+                high: price,
+                low: price,
+                close: price,
+                volume: 0,
+                timestamp: 0,
+            })
+            .collect();
+        self.run_bars(&bars, strategy)
+    }
+
+    // The strategy sees the closes up to and including the current bar; orders fill
+    // at whatever price the fill model picks
+    pub fn run_bars<F>(&self, bars: &[OHLCV], mut strategy: F) -> BacktestResult
+    where
+        F: FnMut(&[f64]) -> SignalType,
+    {
+        let closes: Vec<f64> = bars.iter().map(|b| b.close).collect();
+        let mut pnl_calc = PnLCalculator::new(self.initial_capital);
+        let mut position = Position {
+            symbol: self.symbol.clone(),
+            quantity: 0.0,
+            avg_price: 0.0,
+            current_price: 0.0,
+        };
+        let mut equity_curve = Vec::with_capacity(bars.len());
+        let mut orders_filled = 0;
+        let mut pending: Option<OrderSide> = None;
+
+        let mut fill = |side: OrderSide, bar: &OHLCV, position: &mut Position, pnl_calc: &mut PnLCalculator| {
+            let price = self.fill_model.fill_price(&side, bar);
+            let mut order = Order::new(&self.symbol, side, OrderType::Market, self.trade_quantity);
+            if let Some(fill) = order.execute(price) {
+                pnl_calc.add_realized_pnl(position.apply_fill(fill.quantity, fill.avg_price));
+                orders_filled += 1;
+            }
+        };
+
+        for (i, bar) in bars.iter().enumerate() {
+            if let Some(side) = pending.take() {
+                fill(side, bar, &mut position, &mut pnl_calc);
+            }
+            position.current_price = bar.close;
+
+            let side = match strategy(&closes[..=i]) {
+                SignalType::Buy => Some(OrderSide::Buy),
+                SignalType::Sell => Some(OrderSide::Sell),
+                SignalType::Hold => None,
+            };
+
+            if let Some(side) = side {
+                if self.fill_model.fills_next_bar() {
+                    pending = Some(side);
+                } else {
+                    fill(side, bar, &mut position, &mut pnl_calc);
+                }
+            }
+
+            let report = pnl_calc.calculate_report(std::slice::from_ref(&position));
+            equity_curve.push(self.initial_capital + report.total_pnl);
+        }
+
+        BacktestResult {
+            report: pnl_calc.calculate_report(std::slice::from_ref(&position)),
+            equity_curve,
+            position,
+            orders_filled,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buy_and_hold_rising_series() {
+        let prices: Vec<f64> = (0..20).map(|i| 100.0 + i as f64).collect();
+        let backtester = Backtester::new("TEST", 10_000.0, 10.0);
+        let result = backtester.run(&prices, |history| {
+            if history.len() == 1 {
+                SignalType::Buy
+            } else {
+                SignalType::Hold
+            }
+        });
+
+        assert_eq!(result.orders_filled, 1);
+        assert_eq!(result.position.quantity, 10.0);
+        assert_eq!(result.report.unrealized_pnl, 190.0);
+        assert!(result.report.total_pnl > 0.0);
+        assert_eq!(result.equity_curve.len(), prices.len());
+        assert_eq!(result.equity_curve[0], 10_000.0);
+        assert_eq!(*result.equity_curve.last().unwrap(), 10_190.0);
+    }
+
+    #[test]
+    fn test_round_trip_realizes_pnl() {
+        let prices = vec![100.0, 105.0, 110.0, 108.0];
+        let backtester = Backtester::new("TEST", 10_000.0, 5.0);
+        let result = backtester.run(&prices, |history| match history.len() {
+            1 => SignalType::Buy,
+            3 => SignalType::Sell,
+            _ => SignalType::Hold,
+        });
+
+        assert_eq!(result.orders_filled, 2);
+        assert_eq!(result.position.quantity, 0.0);
+        assert_eq!(result.report.realized_pnl, 50.0);
+        assert_eq!(result.report.unrealized_pnl, 0.0);
+    }
+
+    #[test]
+    fn test_strategy_never_sees_future_bars() {
+        let prices = vec![1.0, 2.0, 3.0];
+        let mut seen = Vec::new();
+        Backtester::new("TEST", 1_000.0, 1.0).run(&prices, |history| {
+            seen.push(history.len());
+            SignalType::Hold
+        });
+        assert_eq!(seen, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_empty_series() {
+        let result = Backtester::new("TEST", 1_000.0, 1.0).run(&[], |_| SignalType::Buy);
+        assert!(result.equity_curve.is_empty());
+        assert_eq!(result.report.total_pnl, 0.0);
+    }
+
+    fn sample_bar() -> OHLCV {
+        OHLCV::new(101.0, 105.0, 98.0, 103.0, 1_000, 0).unwrap()
+    }
+
+    #[test]
+    fn test_fill_models_pick_expected_price() {
+        let bar = sample_bar();
+        assert_eq!(CloseFill.fill_price(&OrderSide::Buy, &bar), 103.0);
+        assert_eq!(NextOpenFill.fill_price(&OrderSide::Sell, &bar), 101.0);
+        assert_eq!(WorstCaseFill.fill_price(&OrderSide::Buy, &bar), 105.0);
+        assert_eq!(WorstCaseFill.fill_price(&OrderSide::Sell, &bar), 98.0);
+        assert!(NextOpenFill.fills_next_bar());
+        assert!(!CloseFill.fills_next_bar());
+    }
+
+    #[test]
+    fn test_run_bars_with_fill_models() {
+        let bars = vec![sample_bar(), OHLCV::new(104.0, 108.0, 102.0, 107.0, 1_000, 1).unwrap()];
+        let buy_first = |history: &[f64]| if history.len() == 1 { SignalType::Buy } else { SignalType::Hold };
+
+        let close = Backtester::new("TEST", 10_000.0, 10.0).run_bars(&bars, buy_first);
+        assert_eq!(close.position.avg_price, 103.0);
+
+        let worst = Backtester::new("TEST", 10_000.0, 10.0)
+            .with_fill_model(Box::new(WorstCaseFill))
+            .run_bars(&bars, buy_first);
+        assert_eq!(worst.position.avg_price, 105.0);
+
+        let next_open = Backtester::new("TEST", 10_000.0, 10.0)
+            .with_fill_model(Box::new(NextOpenFill))
+            .run_bars(&bars, buy_first);
+        assert_eq!(next_open.position.avg_price, 104.0);
+        // Flat on the signal bar, filled before the second close
+        assert_eq!(next_open.equity_curve, vec![10_000.0, 10_030.0]);
+
+        // A signal on the final bar has no next bar to fill against
+        let late = Backtester::new("TEST", 10_000.0, 10.0)
+            .with_fill_model(Box::new(NextOpenFill))
+            .run_bars(&bars, |history| if history.len() == 2 { SignalType::Buy } else { SignalType::Hold });
+        assert_eq!(late.orders_filled, 0);
+    }
+}
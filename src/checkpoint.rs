@@ -0,0 +1,151 @@
+// Checkpoint
+// Save and resume a whole session: portfolio, trade history, workflow and indicator settings.
+// Files are SavedState-wrapped, so versioning and upgrades happen in saved_state
+
+use std::collections::HashMap;
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+
+use crate::adag::Task;
+use crate::error::BetError;
+use crate::momentum::IndicatorConfig;
+use crate::saved_state::{migrate, SavedState};
+use crate::snapshot::Snapshot;
+use crate::trading::Trade;
+use crate::trading_dag::TradingWorkflow;
+use crate::trading_models::Position;
+use crate::trading_system::TradingSystem;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub initial_capital: f64,
+    pub realized_pnl: f64,
+    pub positions: Vec<Position>,
+    pub trades: Vec<Trade>,
+    pub price_history: HashMap<String, Vec<f64>>,
+    // Workflow steps in execution order
+    pub workflow: Vec<Task>,
+    pub indicators: IndicatorConfig,
+}
+
+impl Checkpoint {
+    pub fn capture(
+        system: &TradingSystem,
+        workflow: &TradingWorkflow,
+        indicators: &IndicatorConfig,
+    ) -> Result<Self, BetError> {
+        Ok(Checkpoint {
+            initial_capital: system.pnl_calc.initial_capital(),
+            realized_pnl: system.pnl_calc.snapshot(),
+            positions: system.positions.clone(),
+            trades: system.blotter.trades().to_vec(),
+            price_history: system.price_history.clone(),
+            workflow: workflow.steps()?,
+            indicators: indicators.clone(),
+        })
+    }
+
+    // A fresh TradingSystem carrying the saved portfolio, P&L, fills and prices.
+    // Fails if the saved trades repeat an id
+    pub fn restore_system(&self) -> Result<TradingSystem, BetError> {
+        let mut system = TradingSystem::new(self.initial_capital);
+        system.pnl_calc.restore(self.realized_pnl);
+        system.positions = self.positions.clone();
+        system.price_history = self.price_history.clone();
+        for trade in &self.trades {
+            system.blotter.record(trade.clone())?;
+        }
+        Ok(system)
+    }
+
+    pub fn restore_workflow(&self) -> Result<TradingWorkflow, BetError> {
+        self.workflow
+            .iter()
+            .cloned()
+            .try_fold(TradingWorkflow::builder(), |builder, task| builder.add_task(task))?
+            .build()
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let json = SavedState::new(self)?.to_json()?;
+        fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path, e))
+    }
+
+    // Older files are migrated to the current schema before the payload is parsed
+    pub fn load(path: &str) -> Result<Checkpoint, String> {
+        let json = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        migrate(&json)?.payload_as()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trading::{Order, OrderSide, OrderType};
+
+    fn populated_system() -> TradingSystem {
+        let mut system = TradingSystem::new(500_000.0);
+        let mut buy = Order::new("MRNA", OrderSide::Buy, OrderType::Market, 100.0);
+        system.submit_order(&mut buy, 120.0, 1).unwrap();
+        let mut sell = Order::new("MRNA", OrderSide::Sell, OrderType::Market, 40.0);
+        system.submit_order(&mut sell, 130.0, 2).unwrap();
+        system.ingest_price("MRNA", 120.0);
+        system.ingest_price("MRNA", 130.0);
+        system
+    }
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        let system = populated_system();
+        let workflow = TradingWorkflow::new();
+        let indicators = IndicatorConfig { rsi_period: 9, ..IndicatorConfig::default() };
+        let checkpoint = Checkpoint::capture(&system, &workflow, &indicators).unwrap();
+
+        let path = std::env::temp_dir().join("bet_checkpoint_round_trip.json");
+        let path = path.to_str().unwrap();
+        checkpoint.save(path).unwrap();
+        let loaded = Checkpoint::load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(loaded.workflow, checkpoint.workflow);
+        let restored = loaded.restore_system().unwrap();
+        assert_eq!(restored.get_portfolio_value(), system.get_portfolio_value());
+        assert_eq!(restored.pnl_calc.snapshot(), 400.0);
+        assert_eq!(restored.blotter.trades(), system.blotter.trades());
+        assert_eq!(restored.blotter.next_id(), system.blotter.next_id());
+        assert_eq!(
+            loaded.restore_workflow().unwrap().get_execution_order().unwrap(),
+            workflow.get_execution_order().unwrap()
+        );
+        assert_eq!(loaded.indicators.rsi_period, 9);
+    }
+
+    #[test]
+    fn test_checkpoint_unknown_version() {
+        let path = std::env::temp_dir().join("bet_checkpoint_unknown_version.json");
+        let path = path.to_str().unwrap();
+        std::fs::write(path, r#"{"schema_version":99,"payload":{}}"#).unwrap();
+        let err = Checkpoint::load(path).unwrap_err();
+        std::fs::remove_file(path).unwrap();
+        assert!(err.contains("Unsupported saved state schema_version 99"));
+    }
+
+    #[test]
+    fn test_checkpoint_version_one_file_migrates() {
+        let checkpoint =
+            Checkpoint::capture(&populated_system(), &TradingWorkflow::new(), &IndicatorConfig::default()).unwrap();
+        let v1 = SavedState {
+            schema_version: 1,
+            payload: serde_json::to_value(&checkpoint).unwrap(),
+        };
+        let path = std::env::temp_dir().join("bet_checkpoint_version_one.json");
+        let path = path.to_str().unwrap();
+        std::fs::write(path, v1.to_json().unwrap()).unwrap();
+        let loaded = Checkpoint::load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(loaded.trades, checkpoint.trades);
+        assert_eq!(loaded.workflow, checkpoint.workflow);
+    }
+}
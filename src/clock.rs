@@ -0,0 +1,60 @@
+// Clock
+// Injectable time source so expiry, windows and staleness can be tested deterministically
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub trait Clock {
+    // Milliseconds since the Unix epoch
+    fn now_ms(&self) -> u64;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_ms(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0)
+    }
+}
+
+// Time only moves when told to
+#[derive(Debug, Clone, Default)]
+pub struct MockClock {
+    now: u64,
+}
+
+impl MockClock {
+    pub fn new(start_ms: u64) -> Self {
+        MockClock { now: start_ms }
+    }
+
+    pub fn advance(&mut self, ms: u64) {
+        self.now += ms;
+    }
+}
+
+impl Clock for MockClock {
+    fn now_ms(&self) -> u64 {
+        self.now
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_clock_advances() {
+        let mut clock = MockClock::new(1_000);
+        assert_eq!(clock.now_ms(), 1_000);
+        clock.advance(250);
+        assert_eq!(clock.now_ms(), 1_250);
+    }
+
+    #[test]
+    fn test_system_clock_is_after_2020() {
+        assert!(SystemClock.now_ms() > 1_577_836_800_000);
+    }
+}
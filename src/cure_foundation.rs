@@ -1,15 +1,48 @@
 // CURE Foundation
 // Coalition for Unified Research and Education in Biotech
 
-#[derive(Debug, Clone)]
+use std::fmt;
+
+use crate::error::BetError;
+use crate::money::{format_money, MoneyFormat};
+
+// Called with (old, new) whenever a project's status changes
+pub type StatusCallback = Box<dyn FnMut(&ProjectStatus, &ProjectStatus) + Send>;
+
 pub struct CureProject {
     pub name: String,
     pub disease_target: String,
     pub funding: f64,
     pub status: ProjectStatus,
+    on_advance: Vec<StatusCallback>,
+}
+
+// Callbacks belong to the original; a clone starts without any
+impl Clone for CureProject {
+    fn clone(&self) -> Self {
+        CureProject {
+            name: self.name.clone(),
+            disease_target: self.disease_target.clone(),
+            funding: self.funding,
+            status: self.status.clone(),
+            on_advance: Vec::new(),
+        }
+    }
+}
+
+impl fmt::Debug for CureProject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CureProject")
+            .field("name", &self.name)
+            .field("disease_target", &self.disease_target)
+            .field("funding", &self.funding)
+            .field("status", &self.status)
+            .field("callbacks", &self.on_advance.len())
+            .finish()
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ProjectStatus {
     Planning,
     Active,
@@ -24,22 +57,37 @@ impl CureProject {
             disease_target: disease_target.to_string(),
             funding,
             status: ProjectStatus::Planning,
+            on_advance: Vec::new(),
         }
     }
 
+    pub fn on_advance(&mut self, cb: impl FnMut(&ProjectStatus, &ProjectStatus) + Send + 'static) {
+        self.on_advance.push(Box::new(cb));
+    }
+
+    // Callbacks only fire on a real transition, not when Approved stays Approved
     pub fn advance_status(&mut self) {
-        self.status = match self.status {
+        let next = match self.status {
             ProjectStatus::Planning => ProjectStatus::Active,
             ProjectStatus::Active => ProjectStatus::Clinical,
             ProjectStatus::Clinical => ProjectStatus::Approved,
             ProjectStatus::Approved => ProjectStatus::Approved,
         };
+        if next == self.status {
+            return;
+        }
+        let old = std::mem::replace(&mut self.status, next);
+        for cb in self.on_advance.iter_mut() {
+            cb(&old, &self.status);
+        }
     }
 }
 
 pub struct CureFoundation {
     projects: Vec<CureProject>,
     total_funding: f64,
+    // Cap on total_funding; None is unlimited
+    budget: Option<f64>,
 }
 
 impl Default for CureFoundation {
@@ -53,12 +101,49 @@ impl CureFoundation {
         CureFoundation {
             projects: Vec::new(),
             total_funding: 0.0,
+            budget: None,
+        }
+    }
+
+    pub fn with_budget(budget: f64) -> Self {
+        CureFoundation {
+            budget: Some(budget),
+            ..Self::new()
         }
     }
 
-    pub fn add_project(&mut self, project: CureProject) {
+    pub fn budget(&self) -> Option<f64> {
+        self.budget
+    }
+
+    // Rejects a project that would push total funding past the budget
+    pub fn add_project(&mut self, project: CureProject) -> Result<(), BetError> {
+        if let Some(budget) = self.budget {
+            if self.total_funding + project.funding > budget {
+                let fmt = MoneyFormat::default();
+                return Err(BetError::Capacity(format!(
+                    "{} needs {} but only {} of the {} budget remains",
+                    project.name,
+                    format_money(project.funding, &fmt),
+                    format_money(budget - self.total_funding, &fmt),
+                    format_money(budget, &fmt)
+                )));
+            }
+        }
         self.total_funding += project.funding;
         self.projects.push(project);
+        Ok(())
+    }
+
+    // Each project's share of total funding, in project order; all zero if nothing is funded
+    pub fn allocation(&self) -> Vec<(String, f64)> {
+        self.projects
+            .iter()
+            .map(|p| {
+                let share = if self.total_funding > 0.0 { p.funding / self.total_funding } else { 0.0 };
+                (p.name.clone(), share)
+            })
+            .collect()
     }
 
     pub fn get_projects(&self) -> &[CureProject] {
@@ -70,34 +155,30 @@ impl CureFoundation {
     }
 
     pub fn display(&self) -> String {
+        self.display_with(&MoneyFormat::default())
+    }
+
+    pub fn display_with(&self, fmt: &MoneyFormat) -> String {
         format!(
-            "CURE Foundation: {} projects, ${:.2}M total funding",
+            "CURE Foundation: {} projects, {}M total funding",
             self.projects.len(),
-            self.total_funding / 1_000_000.0
+            format_money(self.total_funding / 1_000_000.0, fmt)
         )
     }
 }
 
 pub fn initialize_cure_foundation() -> CureFoundation {
     let mut foundation = CureFoundation::new();
-    
-    foundation.add_project(CureProject::new(
-        "Alzheimer's Research Initiative",
-        "Alzheimer's Disease",
-        5_000_000.0,
-    ));
-    
-    foundation.add_project(CureProject::new(
-        "Cancer Immunotherapy Program",
-        "Various Cancers",
-        10_000_000.0,
-    ));
-    
-    foundation.add_project(CureProject::new(
-        "Rare Disease Gene Therapy",
-        "Rare Genetic Disorders",
-        3_000_000.0,
-    ));
+    let projects = [
+        CureProject::new("Alzheimer's Research Initiative", "Alzheimer's Disease", 5_000_000.0),
+        CureProject::new("Cancer Immunotherapy Program", "Various Cancers", 10_000_000.0),
+        CureProject::new("Rare Disease Gene Therapy", "Rare Genetic Disorders", 3_000_000.0),
+    ];
+    for project in projects {
+        foundation
+            .add_project(project)
+            .expect("a foundation without a budget accepts every project");
+    }
 
     foundation
 }
@@ -144,7 +225,7 @@ mod tests {
     fn test_cure_foundation_add_project() {
         let mut foundation = CureFoundation::new();
         let project = CureProject::new("Test", "Disease", 1_000_000.0);
-        foundation.add_project(project);
+        foundation.add_project(project).unwrap();
         
         assert_eq!(foundation.get_projects().len(), 1);
         assert_eq!(foundation.total_funding(), 1_000_000.0);
@@ -164,4 +245,54 @@ mod tests {
         assert_eq!(foundation.get_projects().len(), 3);
         assert_eq!(foundation.total_funding(), 18_000_000.0);
     }
+
+    #[test]
+    fn test_cure_foundation_allocation_sums_to_one() {
+        let foundation = initialize_cure_foundation();
+        let allocation = foundation.allocation();
+        assert_eq!(allocation.len(), 3);
+        assert_eq!(allocation[1].0, "Cancer Immunotherapy Program");
+        assert!((allocation[1].1 - 10.0 / 18.0).abs() < 1e-12);
+        assert!((allocation.iter().map(|(_, share)| share).sum::<f64>() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_cure_foundation_budget_rejects_overrun() {
+        let mut foundation = CureFoundation::with_budget(10_000_000.0);
+        foundation.add_project(CureProject::new("A", "Disease A", 6_000_000.0)).unwrap();
+        let err = foundation.add_project(CureProject::new("B", "Disease B", 5_000_000.0)).unwrap_err();
+        assert_eq!(
+            err,
+            BetError::Capacity("B needs $5000000.00 but only $4000000.00 of the $10000000.00 budget remains".to_string())
+        );
+        assert_eq!(foundation.get_projects().len(), 1);
+        assert_eq!(foundation.total_funding(), 6_000_000.0);
+
+        // Exactly filling the budget is fine
+        foundation.add_project(CureProject::new("C", "Disease C", 4_000_000.0)).unwrap();
+        assert_eq!(foundation.total_funding(), 10_000_000.0);
+    }
+
+    #[test]
+    fn test_on_advance_fires_with_old_and_new() {
+        use std::sync::{Arc, Mutex};
+
+        let transitions = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&transitions);
+        let mut project = CureProject::new("Test", "Disease", 100.0);
+        project.on_advance(move |old, new| sink.lock().unwrap().push((old.clone(), new.clone())));
+
+        project.advance_status();
+        assert_eq!(
+            *transitions.lock().unwrap(),
+            vec![(ProjectStatus::Planning, ProjectStatus::Active)]
+        );
+
+        project.advance_status();
+        project.advance_status();
+        // Already Approved: no transition, no callback
+        project.advance_status();
+        assert_eq!(transitions.lock().unwrap().len(), 3);
+        assert_eq!(transitions.lock().unwrap()[2], (ProjectStatus::Clinical, ProjectStatus::Approved));
+    }
 }
@@ -0,0 +1,134 @@
+// Trading Cycle
+// One pass of the A-DAG workflow in code: fetch -> indicators -> signals -> risk -> execute
+
+use std::collections::HashMap;
+
+use crate::market_data::OHLCV;
+use crate::momentum::{compute_all, IndicatorConfig};
+use crate::signals::{generate_signals, SignalType, TradingSignal};
+use crate::trading::{OrderSide, OrderType, Trade};
+use crate::trading_system::TradingSystem;
+
+// Hard caps an order must stay inside after it fills
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskLimits {
+    // Largest absolute quantity held in any one symbol
+    pub max_position: f64,
+    // Largest sum of absolute market values across the book
+    pub max_gross_exposure: f64,
+}
+
+impl Default for RiskLimits {
+    fn default() -> Self {
+        RiskLimits {
+            max_position: 1_000.0,
+            max_gross_exposure: 1_000_000.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrategyConfig {
+    pub indicators: IndicatorConfig,
+    pub trade_quantity: f64,
+    pub risk: RiskLimits,
+}
+
+impl Default for StrategyConfig {
+    fn default() -> Self {
+        StrategyConfig {
+            indicators: IndicatorConfig::default(),
+            trade_quantity: 100.0,
+            risk: RiskLimits::default(),
+        }
+    }
+}
+
+// Run every symbol (in sorted order) through the workflow on its latest bar and
+// return the trades that executed. Orders fill at the latest close; signals that
+// fail the risk check, or arrive while trading is halted, are dropped
+pub fn run_cycle(system: &mut TradingSystem, bars: &HashMap<String, Vec<OHLCV>>, cfg: &StrategyConfig) -> Vec<Trade> {
+    let mut symbols: Vec<&String> = bars.keys().collect();
+    symbols.sort();
+    let mut trades = Vec::new();
+
+    for symbol in symbols {
+        // Fetch
+        let series = &bars[symbol];
+        let Some(latest) = series.last() else {
+            continue;
+        };
+        let price = latest.close;
+        system.ingest_price(symbol, price);
+        for position in system.positions.iter_mut().filter(|p| &p.symbol == symbol) {
+            position.current_price = price;
+        }
+
+        // Indicators and signal
+        let snapshot = compute_all(series, &cfg.indicators);
+        let (Some(rsi), Some((macd, _, _))) = (snapshot.rsi, snapshot.macd) else {
+            continue;
+        };
+        let side = match generate_signals(rsi, macd) {
+            SignalType::Buy => OrderSide::Buy,
+            SignalType::Sell => OrderSide::Sell,
+            SignalType::Hold => continue,
+        };
+        let reason = format!("RSI {:.1}, MACD {:.2}", rsi, macd);
+        let signal_type = if side == OrderSide::Buy { SignalType::Buy } else { SignalType::Sell };
+        let signal_id = system.add_signal(TradingSignal::new(signal_type, symbol, 1.0, &reason));
+
+        // Risk
+        if !within_limits(system, symbol, side.signed(cfg.trade_quantity), price, &cfg.risk) {
+            continue;
+        }
+
+        // Execute
+        let mut order = system
+            .create_order(symbol, side, OrderType::Market, cfg.trade_quantity)
+            .with_signal_id(signal_id);
+        if let Ok(Some(trade)) = system.submit_order(&mut order, price, latest.timestamp) {
+            trades.push(trade);
+        }
+    }
+
+    trades
+}
+
+fn within_limits(system: &TradingSystem, symbol: &str, delta: f64, price: f64, limits: &RiskLimits) -> bool {
+    let current: f64 = system.positions.iter().filter(|p| p.symbol == symbol).map(|p| p.quantity).sum();
+    let projected = current + delta;
+    let gross_after = system.gross_exposure() - (current * price).abs() + (projected * price).abs();
+    projected.abs() <= limits.max_position && gross_after <= limits.max_gross_exposure
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Long uptrend then a shallow pullback: RSI bottoms out while MACD is still positive
+    fn pullback_bars() -> Vec<OHLCV> {
+        (0..54)
+            .map(|i| {
+                let close = if i < 40 { 100.0 + 2.0 * i as f64 } else { 178.0 - 0.5 * (i - 39) as f64 };
+                OHLCV::new(close, close + 1.0, close - 1.0, close, 1_000, i as u64).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_run_cycle_respects_position_limit() {
+        let mut system = TradingSystem::new(1_000_000.0);
+        let bars = HashMap::from([("MRNA".to_string(), pullback_bars())]);
+        let cfg = StrategyConfig {
+            risk: RiskLimits { max_position: 150.0, ..RiskLimits::default() },
+            ..StrategyConfig::default()
+        };
+
+        assert_eq!(run_cycle(&mut system, &bars, &cfg).len(), 1);
+        // A second buy would take the position to 200 shares
+        assert!(run_cycle(&mut system, &bars, &cfg).is_empty());
+        assert_eq!(system.positions[0].quantity, 100.0);
+        assert_eq!(system.signals.len(), 2);
+    }
+}
@@ -1,12 +1,31 @@
 // Elixir Check
 // Integration layer for Erlang/OTP and Elixir guarantees
 
-use std::process::Command;
+use std::io::{ErrorKind, Read};
+use std::process::{Command, Output, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Upper bound on how long any probe may block the caller
+pub const COMMAND_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Extra attempts a probe gets when the process fails to spawn for a transient reason
+pub const PROBE_RETRIES: usize = 1;
+
+#[cfg(test)]
+thread_local! {
+    static PROBE_RUNS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    static SPAWN_ATTEMPTS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
 
 pub struct ElixirCheck {
     pub has_erlang: bool,
     pub has_elixir: bool,
+    pub has_mix: bool,
+    pub has_gleam: bool,
     pub otp_version: Option<String>,
+    pub otp_major: Option<u32>,
 }
 
 impl Default for ElixirCheck {
@@ -17,88 +36,242 @@ impl Default for ElixirCheck {
 
 impl ElixirCheck {
     pub fn new() -> Self {
-        let has_erlang = Self::check_erlang();
-        let has_elixir = Self::check_elixir();
-        let otp_version = Self::get_otp_version();
+        let mut check = ElixirCheck {
+            has_erlang: false,
+            has_elixir: false,
+            has_mix: false,
+            has_gleam: false,
+            otp_version: None,
+            otp_major: None,
+        };
+        check.refresh();
+        check
+    }
 
-        ElixirCheck {
-            has_erlang,
-            has_elixir,
-            otp_version,
-        }
+    // Re-run the subprocess probes; everything else reads the cached fields
+    pub fn refresh(&mut self) {
+        #[cfg(test)]
+        PROBE_RUNS.with(|runs| runs.set(runs.get() + 1));
+
+        self.has_erlang = Self::check_erlang();
+        self.has_elixir = Self::check_elixir();
+        self.has_mix = Self::check_mix();
+        self.has_gleam = Self::check_gleam();
+        self.otp_version = Self::get_otp_version();
+        self.otp_major = self.otp_version.as_deref().and_then(parse_otp_major);
     }
 
     fn check_erlang() -> bool {
-        // Safe execution with timeout - only checking version
-        match Command::new("erl")
-            .arg("-version")
-            .output()
-        {
-            Ok(output) => output.status.success(),
-            Err(_) => false,
-        }
+        probe_version("erl", "-version")
     }
 
     fn check_elixir() -> bool {
-        // Safe execution with timeout - only checking version
-        match Command::new("elixir")
-            .arg("--version")
-            .output()
-        {
-            Ok(output) => output.status.success(),
-            Err(_) => false,
-        }
+        probe_version("elixir", "--version")
+    }
+
+    fn check_mix() -> bool {
+        probe_version("mix", "--version")
+    }
+
+    fn check_gleam() -> bool {
+        probe_version("gleam", "--version")
     }
 
     fn get_otp_version() -> Option<String> {
         // Safe execution - using fixed, validated arguments only
         // This code path is only used for informational purposes
-        Command::new("erl")
-            .arg("-eval")
-            .arg("erlang:display(erlang:system_info(otp_release)), halt().")
-            .arg("-noshell")
-            .output()
-            .ok()
-            .and_then(|output| {
-                if output.status.success() {
-                    String::from_utf8(output.stdout).ok()
-                } else {
-                    None
-                }
-            })
-            .map(|s| s.trim().to_string())
+        run_checked(
+            "erl",
+            &["-eval", "erlang:display(erlang:system_info(otp_release)), halt().", "-noshell"],
+            COMMAND_TIMEOUT,
+            PROBE_RETRIES,
+        )
+        .ok()
+        .map(|s| clean_otp_release(&s))
+        .filter(|s| !s.is_empty())
     }
 
-    pub fn verify_guarantees(&self) -> Result<String, String> {
-        if !self.has_erlang && !self.has_elixir {
-            return Ok("Erlang/OTP and Elixir not detected (optional)".to_string());
+    pub fn supports_otp(&self, min_major: u32) -> bool {
+        self.otp_major.is_some_and(|major| major >= min_major)
+    }
+
+    pub fn verify_guarantees(&self) -> Result<Guarantees, String> {
+        Ok(Guarantees {
+            erlang_runtime: self.has_erlang,
+            elixir_runtime: self.has_elixir,
+            mix_build_tool: self.has_mix,
+            gleam_compiler: self.has_gleam,
+            fault_tolerance: self.has_erlang,
+            hot_reload: self.has_erlang,
+            immutable_data: self.has_elixir,
+            pattern_matching: self.has_elixir,
+            otp_version: self.otp_version.clone(),
+        })
+    }
+
+    pub fn display(&self) -> String {
+        match self.verify_guarantees() {
+            Ok(guarantees) => format!("Elixir Check:\n{}", guarantees.display()),
+            Err(e) => format!("Elixir Check: {}", e),
+        }
+    }
+}
+
+// Typed view of the runtime guarantees backed by the detected toolchain
+#[derive(Debug, Clone, PartialEq)]
+pub struct Guarantees {
+    pub erlang_runtime: bool,
+    pub elixir_runtime: bool,
+    pub mix_build_tool: bool,
+    pub gleam_compiler: bool,
+    pub fault_tolerance: bool,
+    pub hot_reload: bool,
+    pub immutable_data: bool,
+    pub pattern_matching: bool,
+    pub otp_version: Option<String>,
+}
+
+impl Guarantees {
+    pub fn display(&self) -> String {
+        if !self.erlang_runtime && !self.elixir_runtime && !self.mix_build_tool && !self.gleam_compiler {
+            return "Erlang/OTP and Elixir not detected (optional)".to_string();
         }
 
-        let mut guarantees = Vec::new();
+        let mut lines = Vec::new();
 
-        if self.has_erlang {
-            guarantees.push("✓ Erlang/OTP runtime available".to_string());
-            guarantees.push("✓ Fault tolerance via supervisor trees".to_string());
-            guarantees.push("✓ Hot code reloading support".to_string());
+        if self.erlang_runtime {
+            lines.push("✓ Erlang/OTP runtime available".to_string());
+        }
+        if self.fault_tolerance {
+            lines.push("✓ Fault tolerance via supervisor trees".to_string());
+        }
+        if self.hot_reload {
+            lines.push("✓ Hot code reloading support".to_string());
+        }
+
+        if self.elixir_runtime {
+            lines.push("✓ Elixir runtime available".to_string());
+        }
+        if self.immutable_data {
+            lines.push("✓ Immutable data structures".to_string());
+        }
+        if self.pattern_matching {
+            lines.push("✓ Pattern matching enabled".to_string());
         }
 
-        if self.has_elixir {
-            guarantees.push("✓ Elixir runtime available".to_string());
-            guarantees.push("✓ Immutable data structures".to_string());
-            guarantees.push("✓ Pattern matching enabled".to_string());
+        if self.mix_build_tool {
+            lines.push("✓ Mix build tool available".to_string());
+        }
+        if self.gleam_compiler {
+            lines.push("✓ Gleam compiler available".to_string());
         }
 
         if let Some(ref version) = self.otp_version {
-            guarantees.push(format!("✓ OTP Version: {}", version));
+            lines.push(format!("✓ OTP Version: {}", version));
         }
 
-        Ok(guarantees.join("\n"))
+        lines.join("\n")
     }
+}
 
-    pub fn display(&self) -> String {
-        match self.verify_guarantees() {
-            Ok(guarantees) => format!("Elixir Check:\n{}", guarantees),
-            Err(e) => format!("Elixir Check: {}", e),
+// Strip the quotes and whitespace that erlang:display wraps around otp_release
+pub fn clean_otp_release(raw: &str) -> String {
+    raw.trim().trim_matches('"').trim().to_string()
+}
+
+// Extract the major release from output like "\"26\"\n" or legacy "R16B03"
+pub fn parse_otp_major(raw: &str) -> Option<u32> {
+    let cleaned = clean_otp_release(raw);
+    let release = cleaned.strip_prefix('R').unwrap_or(&cleaned);
+    let digits: String = release.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+// Safe execution with timeout - only checking version
+fn probe_version(program: &str, flag: &str) -> bool {
+    run_checked(program, &[flag], COMMAND_TIMEOUT, PROBE_RETRIES).is_ok()
+}
+
+// Run a command and return its stdout, retrying up to `retries` extra times
+// if the process can't be spawned. Timeouts and non-zero exits are not retried.
+pub fn run_checked(cmd: &str, args: &[&str], timeout: Duration, retries: usize) -> Result<String, String> {
+    let mut attempt = 0;
+    loop {
+        let mut command = Command::new(cmd);
+        command.args(args);
+        match execute(command, timeout) {
+            Ok(output) if output.status.success() => {
+                return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+            }
+            Ok(output) => return Err(format!("{} exited with {}", cmd, output.status)),
+            Err(RunError::TimedOut) => return Err(format!("{} timed out after {:?}", cmd, timeout)),
+            Err(RunError::Spawn(e)) if !is_transient_spawn_error(&e) => {
+                return Err(format!("{} failed to start: {}", cmd, e));
+            }
+            Err(RunError::Spawn(e)) if attempt >= retries => {
+                return Err(format!("{} failed to start after {} attempts: {}", cmd, attempt + 1, e));
+            }
+            Err(RunError::Spawn(_)) => {
+                attempt += 1;
+                thread::sleep(Duration::from_millis(10 * attempt as u64));
+            }
+        }
+    }
+}
+
+enum RunError {
+    Spawn(std::io::Error),
+    TimedOut,
+}
+
+// A missing or non-executable binary won't appear between retries
+fn is_transient_spawn_error(err: &std::io::Error) -> bool {
+    !matches!(err.kind(), ErrorKind::NotFound | ErrorKind::PermissionDenied)
+}
+
+fn execute(mut command: Command, timeout: Duration) -> Result<Output, RunError> {
+    #[cfg(test)]
+    SPAWN_ATTEMPTS.with(|attempts| attempts.set(attempts.get() + 1));
+
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(RunError::Spawn)?;
+
+    // Drain stdout on a separate thread so a chatty child can't fill the pipe and stall
+    let mut stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| RunError::Spawn(std::io::Error::other("stdout not captured")))?;
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        let _ = stdout.read_to_end(&mut buffer);
+        let _ = tx.send(buffer);
+    });
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                // A grandchild that inherited stdout can hold it open after the child
+                // exits; the deadline still applies to collecting the output
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                let stdout = rx.recv_timeout(remaining).map_err(|_| RunError::TimedOut)?;
+                return Ok(Output {
+                    status,
+                    stdout,
+                    stderr: Vec::new(),
+                });
+            }
+            Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(10)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(RunError::TimedOut);
+            }
         }
     }
 }
@@ -115,8 +288,7 @@ mod tests {
     fn test_elixir_check_new() {
         let check = ElixirCheck::new();
         // Test that the check completes without panicking
-        assert!(check.has_erlang || !check.has_erlang);
-        assert!(check.has_elixir || !check.has_elixir);
+        let _ = (check.has_erlang, check.has_elixir);
     }
 
     #[test]
@@ -133,9 +305,176 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_parse_otp_major() {
+        assert_eq!(parse_otp_major("\"26\"\n"), Some(26));
+        assert_eq!(parse_otp_major("  \"25.3\"  "), Some(25));
+        assert_eq!(parse_otp_major("\"R16B03\""), Some(16));
+        assert_eq!(parse_otp_major(""), None);
+        assert_eq!(parse_otp_major("\"unknown\""), None);
+        assert_eq!(clean_otp_release("\"26\"\r\n"), "26");
+    }
+
+    #[test]
+    fn test_supports_otp() {
+        let mut check = ElixirCheck {
+            has_erlang: true,
+            has_elixir: false,
+            has_mix: false,
+            has_gleam: false,
+            otp_version: Some("26".to_string()),
+            otp_major: Some(26),
+        };
+        assert!(check.supports_otp(25));
+        assert!(check.supports_otp(26));
+        assert!(!check.supports_otp(27));
+
+        check.otp_major = None;
+        assert!(!check.supports_otp(1));
+    }
+
+    #[test]
+    fn test_guarantees_reflect_detected_runtimes() {
+        let check = ElixirCheck {
+            has_erlang: true,
+            has_elixir: false,
+            has_mix: false,
+            has_gleam: false,
+            otp_version: Some("26".to_string()),
+            otp_major: Some(26),
+        };
+        let guarantees = check.verify_guarantees().unwrap();
+        assert!(guarantees.erlang_runtime);
+        assert!(guarantees.fault_tolerance);
+        assert!(guarantees.hot_reload);
+        assert!(!guarantees.elixir_runtime);
+        assert!(!guarantees.immutable_data);
+        assert!(!guarantees.pattern_matching);
+        assert_eq!(guarantees.otp_version.as_deref(), Some("26"));
+
+        let display = check.display();
+        assert!(display.contains("Hot code reloading"));
+        assert!(display.contains("OTP Version: 26"));
+        assert!(!display.contains("Pattern matching"));
+    }
+
+    #[test]
+    fn test_guarantees_nothing_detected() {
+        let check = ElixirCheck {
+            has_erlang: false,
+            has_elixir: false,
+            has_mix: false,
+            has_gleam: false,
+            otp_version: None,
+            otp_major: None,
+        };
+        let guarantees = check.verify_guarantees().unwrap();
+        assert!(!guarantees.fault_tolerance && !guarantees.immutable_data);
+        assert!(check.display().contains("not detected (optional)"));
+    }
+
+    #[test]
+    fn test_beam_tools_default_to_false_when_absent() {
+        assert!(!probe_version("definitely-not-mix", "--version"));
+
+        let check = ElixirCheck::new();
+        if Command::new("mix").arg("--version").output().is_err() {
+            assert!(!check.has_mix);
+        }
+        if Command::new("gleam").arg("--version").output().is_err() {
+            assert!(!check.has_gleam);
+        }
+    }
+
+    #[test]
+    fn test_guarantees_include_beam_tools() {
+        let check = ElixirCheck {
+            has_erlang: true,
+            has_elixir: true,
+            has_mix: true,
+            has_gleam: false,
+            otp_version: None,
+            otp_major: None,
+        };
+        let guarantees = check.verify_guarantees().unwrap();
+        assert!(guarantees.mix_build_tool);
+        assert!(!guarantees.gleam_compiler);
+        assert!(check.display().contains("Mix build tool"));
+        assert!(!check.display().contains("Gleam"));
+    }
+
+    #[test]
+    fn test_display_uses_cached_probes() {
+        PROBE_RUNS.with(|runs| runs.set(0));
+        let mut check = ElixirCheck::new();
+        assert_eq!(PROBE_RUNS.with(|runs| runs.get()), 1);
+
+        for _ in 0..5 {
+            let _ = check.display();
+            let _ = check.verify_guarantees();
+        }
+        assert_eq!(PROBE_RUNS.with(|runs| runs.get()), 1);
+
+        check.refresh();
+        assert_eq!(PROBE_RUNS.with(|runs| runs.get()), 2);
+    }
+
+    #[test]
+    fn test_run_checked_missing_binary() {
+        assert!(run_checked("definitely-not-an-installed-binary", &[], COMMAND_TIMEOUT, 0).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_checked_slow_command_times_out() {
+        let start = Instant::now();
+        let err = run_checked("sleep", &["30"], Duration::from_millis(100), 0).unwrap_err();
+        assert!(err.contains("timed out"), "{}", err);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_checked_grandchild_holding_stdout_times_out() {
+        // sh exits at once but the backgrounded sleep keeps the pipe open
+        let start = Instant::now();
+        let err = run_checked("sh", &["-c", "sleep 3 & echo hi"], Duration::from_millis(200), 0).unwrap_err();
+        assert!(err.contains("timed out"), "{}", err);
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_run_checked_bogus_command_fails_without_retrying() {
+        SPAWN_ATTEMPTS.with(|attempts| attempts.set(0));
+        let result = run_checked("definitely-not-an-installed-binary", &["--version"], COMMAND_TIMEOUT, 2);
+        let err = result.unwrap_err();
+        assert!(err.contains("failed to start"), "{}", err);
+        assert!(!err.contains("attempts"), "{}", err);
+        assert_eq!(SPAWN_ATTEMPTS.with(|attempts| attempts.get()), 1);
+    }
+
+    #[test]
+    fn test_transient_spawn_errors() {
+        assert!(!is_transient_spawn_error(&std::io::Error::from(ErrorKind::NotFound)));
+        assert!(!is_transient_spawn_error(&std::io::Error::from(ErrorKind::PermissionDenied)));
+        assert!(is_transient_spawn_error(&std::io::Error::from(ErrorKind::WouldBlock)));
+        assert!(is_transient_spawn_error(&std::io::Error::from(ErrorKind::Interrupted)));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_checked_success_and_failure() {
+        assert_eq!(run_checked("echo", &["hi"], COMMAND_TIMEOUT, 0).unwrap().trim(), "hi");
+
+        // A non-zero exit is a real answer, not a transient failure
+        SPAWN_ATTEMPTS.with(|attempts| attempts.set(0));
+        assert!(run_checked("false", &[], COMMAND_TIMEOUT, 3).is_err());
+        assert_eq!(SPAWN_ATTEMPTS.with(|attempts| attempts.get()), 1);
+    }
+
     #[test]
     fn test_run_elixir_check() {
         let check = run_elixir_check();
-        assert!(check.has_erlang || !check.has_erlang);
+        let _ = check.has_erlang;
     }
 }
@@ -0,0 +1,77 @@
+// Error Types
+// Crate-wide error enum shared by the DAG, persistence and parsing layers
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BetError {
+    Cycle(Vec<String>),
+    UnknownTask(String),
+    DuplicateTask(String),
+    DuplicateTrade(u64),
+    Capacity(String),
+    Halted(String),
+    Parse(String),
+    Io(String),
+}
+
+impl fmt::Display for BetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BetError::Cycle(tasks) => write!(f, "Cycle detected in DAG: {}", tasks.join(", ")),
+            BetError::UnknownTask(id) => write!(f, "Unknown task: {}", id),
+            BetError::DuplicateTask(id) => write!(f, "Duplicate task: {}", id),
+            BetError::DuplicateTrade(id) => write!(f, "Duplicate trade id: {}", id),
+            BetError::Capacity(msg) => write!(f, "Capacity exceeded: {}", msg),
+            BetError::Halted(msg) => write!(f, "Trading halted: {}", msg),
+            BetError::Parse(msg) => write!(f, "Parse error: {}", msg),
+            BetError::Io(msg) => write!(f, "I/O error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for BetError {}
+
+impl From<std::io::Error> for BetError {
+    fn from(err: std::io::Error) -> Self {
+        BetError::Io(err.to_string())
+    }
+}
+
+impl From<serde_json::Error> for BetError {
+    fn from(err: serde_json::Error) -> Self {
+        BetError::Parse(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_display() {
+        let err = BetError::Cycle(vec!["A".to_string(), "B".to_string()]);
+        assert_eq!(err.to_string(), "Cycle detected in DAG: A, B");
+    }
+
+    #[test]
+    fn test_other_variants_display() {
+        assert!(BetError::UnknownTask("X".to_string()).to_string().contains("X"));
+        assert_eq!(BetError::DuplicateTask("X".to_string()).to_string(), "Duplicate task: X");
+        assert!(BetError::Capacity("X".to_string()).to_string().starts_with("Capacity exceeded"));
+        assert!(BetError::Parse("bad".to_string()).to_string().starts_with("Parse error"));
+        assert!(BetError::Io("denied".to_string()).to_string().starts_with("I/O error"));
+    }
+
+    #[test]
+    fn test_from_io_error() {
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "missing");
+        assert!(matches!(BetError::from(io), BetError::Io(msg) if msg.contains("missing")));
+    }
+
+    #[test]
+    fn test_from_json_error() {
+        let json = serde_json::from_str::<u32>("nope").unwrap_err();
+        assert!(matches!(BetError::from(json), BetError::Parse(_)));
+    }
+}
@@ -8,6 +8,8 @@ pub mod momentum;
 pub mod signals;
 pub mod trading;
 pub mod pnl;
+pub mod backtest;
+pub mod sizing;
 pub mod cure_foundation;
 pub mod capital_flow;
 pub mod trading_dag;
@@ -16,3 +18,13 @@ pub mod monad_lambda;
 pub mod storm;
 pub mod swin_transformer;
 pub mod elixir_check;
+pub mod error;
+pub mod rng;
+pub mod simulation;
+pub mod snapshot;
+pub mod audit;
+pub mod checkpoint;
+pub mod clock;
+pub mod cycle;
+pub mod money;
+pub mod saved_state;
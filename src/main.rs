@@ -1,23 +1,34 @@
 // BET Architecture - Main Entry Point
 // Terminal GUI with ANSI escape codes, Modal keyboard system (Vim-style), Menu navigation
 
+use std::fs;
 use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
 use crossterm::{
     cursor,
-    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers,
+        MouseButton, MouseEvent, MouseEventKind,
+    },
     execute, queue,
     style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
     terminal::{self, Clear, ClearType},
 };
 
 use bet_architecture::{
-    elixir_check::run_elixir_check,
+    elixir_check::{run_elixir_check, ElixirCheck},
+    market_data::{resample, OHLCV},
     monad_lambda::demonstrate_monad_system,
     storm::StormTopology,
     swin_transformer::SwinTransformer,
     trading_dag::TradingWorkflow,
+    trading_models::Position,
     trading_system::TradingSystem,
 };
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Mode {
@@ -27,7 +38,7 @@ enum Mode {
 }
 
 #[allow(clippy::upper_case_acronyms)]
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 enum MenuItem {
     TradingSystem,
     StormTopologies,
@@ -35,74 +46,846 @@ enum MenuItem {
     ADAG,
     SwinTransformer,
     ElixirCheck,
+    MarketData,
     Quit,
 }
 
+impl MenuItem {
+    fn all() -> Vec<MenuItem> {
+        vec![
+            MenuItem::TradingSystem,
+            MenuItem::StormTopologies,
+            MenuItem::MonadLambda,
+            MenuItem::ADAG,
+            MenuItem::SwinTransformer,
+            MenuItem::ElixirCheck,
+            MenuItem::MarketData,
+            MenuItem::Quit,
+        ]
+    }
+
+    // Name accepted by `:goto`, matched by prefix
+    fn command_name(self) -> &'static str {
+        match self {
+            MenuItem::TradingSystem => "trading system",
+            MenuItem::StormTopologies => "storm topologies",
+            MenuItem::MonadLambda => "monad lambda",
+            MenuItem::ADAG => "adag",
+            MenuItem::SwinTransformer => "swin transformer",
+            MenuItem::ElixirCheck => "elixir check",
+            MenuItem::MarketData => "market data",
+            MenuItem::Quit => "quit",
+        }
+    }
+}
+
+// Action resolved from a line typed in command mode
+#[derive(Debug, Clone, PartialEq)]
+enum CommandAction {
+    Quit,
+    Refresh,
+    Goto(MenuItem),
+    SetInterval(Duration),
+    SetCapital(f64),
+    Unknown(String),
+}
+
+// Accepts plain or formatted amounts such as 250000, 2.5e6 or $1,000,000
+fn parse_capital(input: &str) -> Result<f64, String> {
+    let cleaned: String = input.trim().trim_start_matches('$').chars().filter(|&c| c != ',').collect();
+    match cleaned.parse::<f64>() {
+        Ok(amount) if amount.is_finite() && amount > 0.0 => Ok(amount),
+        Ok(_) => Err(format!("Capital must be a positive amount: {}", input.trim())),
+        Err(_) => Err(format!("Invalid capital amount: {}", input.trim())),
+    }
+}
+
+fn parse_command(input: &str) -> CommandAction {
+    let input = input.trim().trim_start_matches(':').trim();
+    let mut parts = input.splitn(2, char::is_whitespace);
+    let name = parts.next().unwrap_or("");
+    let arg = parts.next().unwrap_or("").trim().to_lowercase();
+
+    match name {
+        "q" | "quit" => CommandAction::Quit,
+        "refresh" => CommandAction::Refresh,
+        "goto" if !arg.is_empty() => {
+            let target = arg.split_whitespace().collect::<Vec<_>>().join(" ");
+            MenuItem::all()
+                .into_iter()
+                .find(|item| item.command_name().starts_with(&target))
+                .map(CommandAction::Goto)
+                .unwrap_or_else(|| CommandAction::Unknown(format!("No menu item matching '{}'", arg)))
+        }
+        "goto" => CommandAction::Unknown("Usage: :goto <item>".to_string()),
+        "interval" => match arg.parse::<u64>() {
+            Ok(ms) if ms > 0 => CommandAction::SetInterval(Duration::from_millis(ms)),
+            _ => CommandAction::Unknown("Usage: :interval <milliseconds>".to_string()),
+        },
+        "capital" if arg.is_empty() => CommandAction::Unknown("Usage: :capital <amount>".to_string()),
+        "capital" => match parse_capital(&arg) {
+            Ok(amount) => CommandAction::SetCapital(amount),
+            Err(message) => CommandAction::Unknown(message),
+        },
+        _ => CommandAction::Unknown(format!("Not an editor command: {}", input)),
+    }
+}
+
+// Case-insensitive subsequence match; None if the query can't be found in order.
+// Consecutive runs and matches on word starts (CamelCase humps) score higher.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let chars: Vec<char> = candidate.chars().collect();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut last_match: Option<usize> = None;
+
+    for q in query.chars().filter(|c| !c.is_whitespace()) {
+        let q = q.to_ascii_lowercase();
+        let offset = chars[pos..].iter().position(|c| c.to_ascii_lowercase() == q)?;
+        let idx = pos + offset;
+
+        score += 1;
+        if last_match == Some(idx.wrapping_sub(1)) {
+            score += 2;
+        }
+        let at_word_start = idx == 0 || (chars[idx].is_uppercase() && chars[idx - 1].is_lowercase());
+        if at_word_start {
+            score += 3;
+        }
+
+        last_match = Some(idx);
+        pos = idx + 1;
+    }
+
+    Some(score)
+}
+
+// Keybindings listed in the help overlay, grouped by mode
+const HELP_SECTIONS: &[(&str, &[(&str, &str)])] = &[
+    (
+        "NORMAL",
+        &[
+            ("j / ↓", "Next menu item"),
+            ("k / ↑", "Previous menu item"),
+            ("<count>j / <count>k", "Move by count items (e.g. 3j)"),
+            ("gg / G", "Jump to first / last menu item"),
+            ("Ctrl-D / PageDown", "Scroll content down"),
+            ("Ctrl-U / PageUp", "Scroll content up"),
+            ("/", "Search menu (fuzzy), or filter positions on Trading"),
+            (":", "Enter command mode"),
+            ("L", "Toggle live market refresh"),
+            ("t", "Toggle dark / light theme"),
+            ("?", "Toggle this help"),
+            ("q / Ctrl-C", "Quit"),
+        ],
+    ),
+    (
+        "COMMAND",
+        &[
+            (":q / :quit", "Quit"),
+            (":refresh", "Re-run the Elixir check"),
+            (":goto <item>", "Select a menu item by name"),
+            (":interval <ms>", "Set the live refresh interval"),
+            (":capital <amount>", "Set initial capital, keeping positions"),
+            ("↑ / ↓", "Recall previous / next command"),
+            ("Enter", "Run command"),
+            ("Esc", "Cancel"),
+        ],
+    ),
+    (
+        "INSERT (SEARCH)",
+        &[
+            ("<text>", "Filter the menu"),
+            ("Backspace", "Delete last character"),
+            ("Enter", "Keep filter and return to Normal"),
+            ("Esc", "Clear filter and return to Normal"),
+        ],
+    ),
+];
+
+const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_millis(1000);
+// How long the loop waits for a key before checking for ticks again
+const INPUT_POLL: Duration = Duration::from_millis(50);
+const SCROLL_STEP: i32 = 10;
+const MOUSE_SCROLL_STEP: i32 = 3;
+// Header plus the mode line and its spacer come before the "MENU:" label
+const MENU_LABEL_ROW: u16 = 4;
+const MAX_COUNT: usize = 9999;
+
+// Terminal size assumed until the first size query or resize event
+const DEFAULT_TERMINAL_SIZE: (u16, u16) = (80, 24);
+const HEADER_TITLE: &str = "BET ARCHITECTURE SYSTEM";
+
+// A horizontal border of exactly `width` columns, e.g. ╔════╗
+fn border_line(left: char, fill: char, right: char, width: usize) -> String {
+    if width < 2 {
+        return fill.to_string().repeat(width);
+    }
+    format!("{}{}{}", left, fill.to_string().repeat(width - 2), right)
+}
+
+// Cut a line down to `width` characters so it never wraps onto the next row
+fn truncate_to_width(line: &str, width: usize) -> String {
+    line.chars().take(width).collect()
+}
+
+const SPARK_BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+const EQUITY_SPARKLINE_WIDTH: usize = 60;
+
+// One block per value, scaled between the data's min and max. Longer series are
+// averaged down into `width` buckets; constant data draws a flat mid-height line
+fn sparkline(values: &[f64], width: usize) -> String {
+    if values.is_empty() || width == 0 {
+        return String::new();
+    }
+    let buckets = values.len().min(width);
+    let points: Vec<f64> = (0..buckets)
+        .map(|i| {
+            let bucket = &values[i * values.len() / buckets..(i + 1) * values.len() / buckets];
+            bucket.iter().sum::<f64>() / bucket.len() as f64
+        })
+        .collect();
+
+    let min = points.iter().copied().fold(f64::MAX, f64::min);
+    let max = points.iter().copied().fold(f64::MIN, f64::max);
+    let top = SPARK_BLOCKS.len() - 1;
+    points
+        .iter()
+        .map(|&v| {
+            if max == min {
+                SPARK_BLOCKS[top / 2]
+            } else {
+                SPARK_BLOCKS[(((v - min) / (max - min)) * top as f64).round() as usize]
+            }
+        })
+        .collect()
+}
+
+// Positions whose symbol contains `query`, ignoring case; an empty query keeps all
+fn filter_positions<'a>(positions: &'a [Position], query: &str) -> Vec<&'a Position> {
+    let query = query.to_uppercase();
+    positions
+        .iter()
+        .filter(|p| p.symbol.to_uppercase().contains(&query))
+        .collect()
+}
+
+// Characters for candle bodies and wicks; the ASCII set is for terminals without Unicode
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CandleGlyphs {
+    up: char,
+    down: char,
+    wick: char,
+}
+
+const UNICODE_CANDLES: CandleGlyphs = CandleGlyphs { up: '█', down: '░', wick: '│' };
+const ASCII_CANDLES: CandleGlyphs = CandleGlyphs { up: '#', down: '=', wick: '|' };
+const CANDLE_TICKS: usize = 5;
+const CANDLE_HEIGHT: usize = 8;
+const CANDLE_INDENT: &str = "    ";
+
+// ASCII only when the locale names a non-UTF-8 encoding; an unset locale keeps Unicode
+// like the rest of the UI
+fn candle_glyphs_for_locale(locale: Option<&str>) -> CandleGlyphs {
+    match locale.map(str::to_lowercase) {
+        Some(locale) if !locale.is_empty() && !locale.contains("utf-8") && !locale.contains("utf8") => {
+            ASCII_CANDLES
+        }
+        _ => UNICODE_CANDLES,
+    }
+}
+
+fn terminal_candle_glyphs() -> CandleGlyphs {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty());
+    candle_glyphs_for_locale(locale.as_deref())
+}
+
+// One column per bar, `height` rows scaled between the lowest low and highest high.
+// Bodies span open..close (up or down glyph), wicks span low..high. Colour is left to
+// the caller, per column. A flat series draws everything on the middle row
+fn render_candles(bars: &[OHLCV], height: usize, glyphs: CandleGlyphs) -> String {
+    if bars.is_empty() || height == 0 {
+        return String::new();
+    }
+    let min = bars.iter().map(|b| b.low).fold(f64::MAX, f64::min);
+    let max = bars.iter().map(|b| b.high).fold(f64::MIN, f64::max);
+    let row_of = |price: f64| {
+        if max == min {
+            (height - 1) / 2
+        } else {
+            (((price - min) / (max - min)) * (height - 1) as f64).round() as usize
+        }
+    };
+
+    (0..height)
+        .rev()
+        .map(|row| {
+            bars.iter()
+                .map(|bar| {
+                    let (body_lo, body_hi) = if bar.close >= bar.open {
+                        (row_of(bar.open), row_of(bar.close))
+                    } else {
+                        (row_of(bar.close), row_of(bar.open))
+                    };
+                    if (body_lo..=body_hi).contains(&row) {
+                        if bar.close >= bar.open { glyphs.up } else { glyphs.down }
+                    } else if (row_of(bar.low)..=row_of(bar.high)).contains(&row) {
+                        glyphs.wick
+                    } else {
+                        ' '
+                    }
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Menu index under a clicked row, given the row of the first menu item
+fn menu_index_at_row(row: u16, menu_top: u16, item_count: usize) -> Option<usize> {
+    let index = row.checked_sub(menu_top)? as usize;
+    (index < item_count).then_some(index)
+}
+
+// Keep the scroll offset within the content so the last page stays filled
+fn clamp_scroll(scroll: u16, content_len: usize, visible: usize) -> u16 {
+    let max_scroll = content_len.saturating_sub(visible);
+    (scroll as usize).min(max_scroll) as u16
+}
+
+// Colors used across the header, menu, content pane and footer
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Theme {
+    header_bg: Color,
+    header_fg: Color,
+    mode: Color,
+    command: Color,
+    error: Color,
+    search: Color,
+    section: Color,
+    selected_bg: Color,
+    selected_fg: Color,
+    rule: Color,
+    title: Color,
+    body: Color,
+    muted: Color,
+    candle_up: Color,
+    candle_down: Color,
+}
+
+impl Theme {
+    fn dark() -> Self {
+        Theme {
+            header_bg: Color::Blue,
+            header_fg: Color::White,
+            mode: Color::Yellow,
+            command: Color::Magenta,
+            error: Color::Red,
+            search: Color::Cyan,
+            section: Color::Green,
+            selected_bg: Color::White,
+            selected_fg: Color::Black,
+            rule: Color::Cyan,
+            title: Color::Yellow,
+            body: Color::Reset,
+            muted: Color::DarkGrey,
+            candle_up: Color::Green,
+            candle_down: Color::Red,
+        }
+    }
+
+    fn light() -> Self {
+        Theme {
+            header_bg: Color::DarkBlue,
+            header_fg: Color::White,
+            mode: Color::DarkYellow,
+            command: Color::DarkMagenta,
+            error: Color::DarkRed,
+            search: Color::DarkCyan,
+            section: Color::DarkGreen,
+            selected_bg: Color::DarkBlue,
+            selected_fg: Color::White,
+            rule: Color::DarkCyan,
+            title: Color::DarkMagenta,
+            body: Color::Reset,
+            muted: Color::Grey,
+            candle_up: Color::DarkGreen,
+            candle_down: Color::DarkRed,
+        }
+    }
+
+    fn toggled(&self) -> Self {
+        if *self == Theme::dark() {
+            Theme::light()
+        } else {
+            Theme::dark()
+        }
+    }
+}
+
+const DEFAULT_INITIAL_CAPITAL: f64 = 1_000_000.0;
+
+// Session state remembered between launches
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SessionConfig {
+    selected_menu_item: MenuItem,
+    initial_capital: f64,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        SessionConfig {
+            selected_menu_item: MenuItem::TradingSystem,
+            initial_capital: DEFAULT_INITIAL_CAPITAL,
+        }
+    }
+}
+
+impl SessionConfig {
+    // Missing, unreadable or nonsensical configs fall back to defaults
+    fn load(path: &str) -> SessionConfig {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<SessionConfig>(&json).ok())
+            .filter(|config| config.initial_capital.is_finite() && config.initial_capital > 0.0)
+            .unwrap_or_default()
+    }
+
+    fn save(&self, path: &str) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, json)
+    }
+}
+
+fn config_path() -> PathBuf {
+    std::env::var_os("HOME")
+        .map(PathBuf::from)
+        .unwrap_or_default()
+        .join(".bet_session.json")
+}
+
+// Events pushed by the background market simulation thread
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TickEvent {
+    PriceUpdate(u64),
+}
+
+// Emit a PriceUpdate every interval until told to stop, the receiver goes away,
+// or `limit` ticks have been sent
+fn produce_ticks(tx: Sender<TickEvent>, stop: Receiver<()>, interval: Duration, limit: Option<u64>) {
+    let mut sent = 0;
+    while limit.is_none_or(|limit| sent < limit) {
+        match stop.recv_timeout(interval) {
+            Err(RecvTimeoutError::Timeout) => {}
+            _ => return,
+        }
+        sent += 1;
+        if tx.send(TickEvent::PriceUpdate(sent)).is_err() {
+            return;
+        }
+    }
+}
+
+// Owns the tick thread; stopping (or dropping) it joins the thread
+struct TickSource {
+    interval: Duration,
+    ticks: Receiver<TickEvent>,
+    stop: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl TickSource {
+    fn start(interval: Duration) -> Self {
+        let (tx, ticks) = mpsc::channel();
+        let (stop, stop_rx) = mpsc::channel();
+        let handle = thread::spawn(move || produce_ticks(tx, stop_rx, interval, None));
+        TickSource {
+            interval,
+            ticks,
+            stop: Some(stop),
+            handle: Some(handle),
+        }
+    }
+
+    fn stop(&mut self) {
+        // Dropping the sender wakes the thread's recv_timeout immediately
+        self.stop.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for TickSource {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+// Start, restart or stop the tick thread to match live mode and the current interval
+fn sync_tick_source(app: &App, source: &mut Option<TickSource>) {
+    let wanted = app.live_refresh.then_some(app.refresh_interval);
+    if source.as_ref().map(|s| s.interval) != wanted {
+        *source = wanted.map(TickSource::start);
+    }
+}
+
 struct App {
     mode: Mode,
     selected_menu_item: MenuItem,
     search_query: String,
+    // The query filters the trading panel's positions instead of the menu
+    content_search: bool,
+    command_buffer: String,
+    command_history: Vec<String>,
+    history_cursor: Option<usize>,
+    status_message: Option<String>,
+    content_scroll: u16,
+    pending_count: Option<usize>,
+    pending_key: Option<char>,
+    show_help: bool,
+    live_refresh: bool,
+    refresh_interval: Duration,
+    tick: u64,
+    terminal_size: (u16, u16),
+    theme: Theme,
+    candle_glyphs: CandleGlyphs,
     trading_system: TradingSystem,
     storm: StormTopology,
     swin: SwinTransformer,
     trading_workflow: TradingWorkflow,
+    elixir_check: ElixirCheck,
 }
 
 impl App {
+    #[cfg(test)]
     fn new() -> Self {
+        Self::with_config(&SessionConfig::default())
+    }
+
+    fn with_config(config: &SessionConfig) -> Self {
         App {
             mode: Mode::Normal,
-            selected_menu_item: MenuItem::TradingSystem,
+            selected_menu_item: config.selected_menu_item,
             search_query: String::new(),
-            trading_system: TradingSystem::new(1_000_000.0),
+            content_search: false,
+            command_buffer: String::new(),
+            command_history: Vec::new(),
+            history_cursor: None,
+            status_message: None,
+            content_scroll: 0,
+            pending_count: None,
+            pending_key: None,
+            show_help: false,
+            live_refresh: false,
+            refresh_interval: DEFAULT_REFRESH_INTERVAL,
+            tick: 0,
+            terminal_size: terminal::size().unwrap_or(DEFAULT_TERMINAL_SIZE),
+            theme: Theme::dark(),
+            candle_glyphs: terminal_candle_glyphs(),
+            trading_system: TradingSystem::new(config.initial_capital),
             storm: StormTopology::new(),
             swin: SwinTransformer::with_16_heads(),
             trading_workflow: TradingWorkflow::new(),
+            elixir_check: run_elixir_check(),
+        }
+    }
+
+    fn load_config(path: &str) -> Self {
+        Self::with_config(&SessionConfig::load(path))
+    }
+
+    fn save_config(&self, path: &str) -> io::Result<()> {
+        SessionConfig {
+            selected_menu_item: self.selected_menu_item,
+            initial_capital: self.trading_system.pnl_calc.initial_capital(),
         }
+        .save(path)
     }
 
     fn get_menu_items(&self) -> Vec<MenuItem> {
-        let all_items = vec![
-            MenuItem::TradingSystem,
-            MenuItem::StormTopologies,
-            MenuItem::MonadLambda,
-            MenuItem::ADAG,
-            MenuItem::SwinTransformer,
-            MenuItem::ElixirCheck,
-            MenuItem::Quit,
-        ];
+        let all_items = MenuItem::all();
 
-        if self.search_query.is_empty() {
+        if self.search_query.is_empty() || self.content_search {
             all_items
         } else {
-            all_items
+            let mut scored: Vec<(i32, MenuItem)> = all_items
                 .into_iter()
-                .filter(|item| {
-                    let name = format!("{:?}", item).to_lowercase();
-                    name.contains(&self.search_query.to_lowercase())
+                .filter_map(|item| {
+                    fuzzy_score(&self.search_query, &format!("{:?}", item)).map(|score| (score, item))
                 })
-                .collect()
+                .collect();
+            // Stable sort keeps menu order among equally good matches
+            scored.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+            scored.into_iter().map(|(_, item)| item).collect()
         }
     }
 
-    fn next_menu_item(&mut self) {
+    fn next_menu_item(&mut self, count: usize) {
         let items = self.get_menu_items();
         if let Some(current_idx) = items.iter().position(|&item| item == self.selected_menu_item) {
-            let next_idx = (current_idx + 1) % items.len();
-            self.selected_menu_item = items[next_idx];
+            let next_idx = (current_idx + count % items.len()) % items.len();
+            self.select_menu_item(items[next_idx]);
         }
     }
 
-    fn prev_menu_item(&mut self) {
+    fn prev_menu_item(&mut self, count: usize) {
         let items = self.get_menu_items();
         if let Some(current_idx) = items.iter().position(|&item| item == self.selected_menu_item) {
-            let prev_idx = if current_idx == 0 {
-                items.len() - 1
-            } else {
-                current_idx - 1
+            let prev_idx = (current_idx + items.len() - count % items.len()) % items.len();
+            self.select_menu_item(items[prev_idx]);
+        }
+    }
+
+    fn select_first_menu_item(&mut self) {
+        if let Some(&first) = self.get_menu_items().first() {
+            self.select_menu_item(first);
+        }
+    }
+
+    fn select_last_menu_item(&mut self) {
+        if let Some(&last) = self.get_menu_items().last() {
+            self.select_menu_item(last);
+        }
+    }
+
+    fn push_count_digit(&mut self, digit: usize) {
+        let count = self.pending_count.unwrap_or(0) * 10 + digit;
+        self.pending_count = Some(count.min(MAX_COUNT));
+    }
+
+    fn select_menu_item(&mut self, item: MenuItem) {
+        if item != self.selected_menu_item {
+            self.content_scroll = 0;
+            if self.content_search {
+                self.search_query.clear();
+            }
+        }
+        self.selected_menu_item = item;
+    }
+
+    fn content_lines(&self) -> Vec<String> {
+        let text = match self.selected_menu_item {
+            MenuItem::TradingSystem => {
+                let live = if self.live_refresh {
+                    format!("ON (tick {}, every {}ms)", self.tick, self.refresh_interval.as_millis())
+                } else {
+                    "OFF (press L to start)".to_string()
+                };
+                let width = EQUITY_SPARKLINE_WIDTH.min(self.width().saturating_sub(12));
+                let mut text = format!(
+                    "{}\n  Live Refresh: {}\n  Equity: {}",
+                    self.trading_system.display_summary(),
+                    live,
+                    sparkline(self.trading_system.equity_curve(), width)
+                );
+                if self.content_search && !self.search_query.is_empty() {
+                    let matches = filter_positions(&self.trading_system.positions, &self.search_query);
+                    text.push_str(&format!("\n  Positions matching \"{}\":", self.search_query));
+                    if matches.is_empty() {
+                        text.push_str("\n    no matches");
+                    }
+                    for p in matches {
+                        text.push_str(&format!(
+                            "\n    {} {} @ {:.2} (P&L: {:.2})",
+                            p.symbol,
+                            p.quantity,
+                            p.current_price,
+                            p.unrealized_pnl()
+                        ));
+                    }
+                }
+                text
+            }
+            MenuItem::StormTopologies => self.storm.display(),
+            MenuItem::MonadLambda => demonstrate_monad_system(),
+            MenuItem::ADAG => {
+                let mut text = format!("{}\nTrading Workflow:", self.trading_workflow.display());
+                if let Ok(order) = self.trading_workflow.get_execution_order() {
+                    for (i, task) in order.iter().enumerate() {
+                        text.push_str(&format!("\n  {}. {}", i + 1, task));
+                    }
+                }
+                match self.trading_workflow.display_tree() {
+                    Ok(tree) => text.push_str(&format!("\nDependency Layout:\n{}", tree)),
+                    Err(e) => text.push_str(&format!("\nDependency Layout: {}", e)),
+                }
+                text
+            }
+            MenuItem::SwinTransformer => self.swin.display(),
+            MenuItem::ElixirCheck => self.elixir_check.display(),
+            MenuItem::MarketData => self
+                .market_lines()
+                .into_iter()
+                .map(|(line, _)| line)
+                .collect::<Vec<_>>()
+                .join("\n"),
+            MenuItem::Quit => "Press 'q' or Ctrl+C to exit".to_string(),
+        };
+        text.lines().map(|line| line.to_string()).collect()
+    }
+
+    // Market data panel lines. Chart rows carry each column's direction (true = up)
+    // so draw_content can colour them
+    fn market_lines(&self) -> Vec<(String, Option<Vec<bool>>)> {
+        let feed = &self.trading_system.market_feed;
+        let symbols = feed.bar_symbols();
+        if symbols.is_empty() {
+            return vec![("  No bars yet (press L to start live refresh)".to_string(), None)];
+        }
+
+        let columns = self.width().saturating_sub(CANDLE_INDENT.len());
+        let mut lines = vec![(format!("  {} ticks per candle", CANDLE_TICKS), None)];
+        for symbol in symbols {
+            let series = resample(feed.bars(symbol), CANDLE_TICKS);
+            let Some(last) = series.last() else {
+                continue;
             };
-            self.selected_menu_item = items[prev_idx];
+            lines.push((format!("  {} (last {:.2})", symbol, last.close), None));
+            if columns == 0 {
+                lines.push((format!("{}(too narrow for candles)", CANDLE_INDENT), None));
+                continue;
+            }
+            let bars = &series[series.len().saturating_sub(columns)..];
+            let ups: Vec<bool> = bars.iter().map(|bar| bar.close >= bar.open).collect();
+            for row in render_candles(bars, CANDLE_HEIGHT, self.candle_glyphs).lines() {
+                lines.push((format!("{}{}", CANDLE_INDENT, row), Some(ups.clone())));
+            }
+        }
+        lines
+    }
+
+    // Rows left for the content pane once header, menu and footer are drawn
+    fn visible_content_rows(&self) -> usize {
+        let rows = self.terminal_size.1 as usize;
+        let mut chrome = 3 + 2 + 1 + self.get_menu_items().len() + 1 + 2 + 1 + 4;
+        if self.mode == Mode::Command || self.status_message.is_some() {
+            chrome += 2;
+        }
+        if !self.search_query.is_empty() || self.mode == Mode::Insert {
+            chrome += 2;
         }
+        rows.saturating_sub(chrome).max(1)
+    }
+
+    // Screen row of the first menu item; mirrors the optional lines draw_ui puts above the menu
+    fn menu_top_row(&self) -> u16 {
+        let mut row = MENU_LABEL_ROW + 1;
+        if self.mode == Mode::Command || self.status_message.is_some() {
+            row += 2;
+        }
+        if !self.search_query.is_empty() || self.mode == Mode::Insert {
+            row += 2;
+        }
+        row
+    }
+
+    fn resize(&mut self, width: u16, height: u16) {
+        self.terminal_size = (width, height);
+        // A taller terminal may leave the old offset past the last page
+        self.scroll_content(0);
+    }
+
+    fn width(&self) -> usize {
+        self.terminal_size.0 as usize
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        if self.show_help {
+            return;
+        }
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let items = self.get_menu_items();
+                if let Some(index) = menu_index_at_row(mouse.row, self.menu_top_row(), items.len()) {
+                    self.select_menu_item(items[index]);
+                }
+            }
+            MouseEventKind::ScrollDown => self.scroll_content(MOUSE_SCROLL_STEP),
+            MouseEventKind::ScrollUp => self.scroll_content(-MOUSE_SCROLL_STEP),
+            _ => {}
+        }
+    }
+
+    fn scroll_content(&mut self, delta: i32) {
+        let target = (self.content_scroll as i32 + delta).max(0) as u16;
+        self.content_scroll =
+            clamp_scroll(target, self.content_lines().len(), self.visible_content_rows());
+    }
+
+    // Returns true if the command asks the app to exit
+    fn execute_command(&mut self, action: CommandAction) -> bool {
+        self.status_message = None;
+        match action {
+            CommandAction::Quit => return true,
+            CommandAction::Refresh => self.elixir_check.refresh(),
+            CommandAction::Goto(item) => {
+                self.search_query.clear();
+                self.select_menu_item(item);
+            }
+            CommandAction::SetInterval(interval) => self.refresh_interval = interval,
+            CommandAction::SetCapital(amount) => self.trading_system.set_initial_capital(amount),
+            CommandAction::Unknown(message) => self.status_message = Some(message),
+        }
+        false
+    }
+
+    fn push_history(&mut self, command: &str) {
+        let command = command.trim();
+        if !command.is_empty() && self.command_history.last().map(String::as_str) != Some(command) {
+            self.command_history.push(command.to_string());
+        }
+        self.history_cursor = None;
+    }
+
+    fn history_prev(&mut self) {
+        if self.command_history.is_empty() {
+            return;
+        }
+        let idx = match self.history_cursor {
+            Some(idx) => idx.saturating_sub(1),
+            None => self.command_history.len() - 1,
+        };
+        self.history_cursor = Some(idx);
+        self.command_buffer = self.command_history[idx].clone();
+    }
+
+    fn history_next(&mut self) {
+        match self.history_cursor {
+            Some(idx) if idx + 1 < self.command_history.len() => {
+                self.history_cursor = Some(idx + 1);
+                self.command_buffer = self.command_history[idx + 1].clone();
+            }
+            Some(_) => {
+                self.history_cursor = None;
+                self.command_buffer.clear();
+            }
+            None => {}
+        }
+    }
+
+    fn toggle_live_refresh(&mut self) {
+        self.live_refresh = !self.live_refresh;
+        if self.live_refresh && self.trading_system.positions.is_empty() {
+            // Give the live panel something to move: a demo book across the universe
+            for symbol in self.trading_system.biotech_symbols.clone() {
+                self.trading_system.add_position(Position {
+                    symbol: symbol.ticker,
+                    quantity: 100.0,
+                    avg_price: 100.0,
+                    current_price: 100.0,
+                });
+            }
+        }
+    }
+
+    fn on_tick(&mut self) {
+        self.tick += 1;
+        self.trading_system.simulate_tick(self.tick);
     }
 }
 
@@ -110,13 +893,14 @@ fn main() -> io::Result<()> {
     // Enable raw mode for terminal
     terminal::enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+    execute!(stdout, terminal::EnterAlternateScreen, EnableMouseCapture, cursor::Hide)?;
 
     let result = run_app(&mut stdout);
 
     // Restore terminal
     execute!(
         stdout,
+        DisableMouseCapture,
         terminal::LeaveAlternateScreen,
         cursor::Show,
         ResetColor
@@ -127,45 +911,119 @@ fn main() -> io::Result<()> {
 }
 
 fn run_app<W: Write>(stdout: &mut W) -> io::Result<()> {
-    let mut app = App::new();
+    let config_path = config_path();
+    let config_path = config_path.to_string_lossy();
+    let mut app = App::load_config(&config_path);
+
+    let mut ticks: Option<TickSource> = None;
+    let mut dirty = true;
 
     loop {
-        draw_ui(stdout, &app)?;
+        sync_tick_source(&app, &mut ticks);
+        if dirty {
+            draw_ui(stdout, &app)?;
+            dirty = false;
+        }
 
-        if let Event::Key(key) = event::read()? {
-            match app.mode {
-                Mode::Normal => {
-                    if handle_normal_mode(&mut app, key) {
-                        break;
-                    }
-                }
-                Mode::Command => {
-                    if handle_command_mode(&mut app, key) {
-                        break;
-                    }
-                }
-                Mode::Insert => {
-                    if handle_insert_mode(&mut app, key) {
-                        break;
-                    }
+        // Ticks arrive on a channel, so waiting for keys never stalls the simulated market
+        if let Some(source) = &ticks {
+            for TickEvent::PriceUpdate(_) in source.ticks.try_iter() {
+                app.on_tick();
+                dirty = true;
+            }
+        }
+
+        if !event::poll(INPUT_POLL)? {
+            continue;
+        }
+        dirty = true;
+
+        match event::read()? {
+            Event::Key(key) => {
+                let quit = match app.mode {
+                    Mode::Normal => handle_normal_mode(&mut app, key),
+                    Mode::Command => handle_command_mode(&mut app, key),
+                    Mode::Insert => handle_insert_mode(&mut app, key),
+                };
+                if quit {
+                    break;
                 }
             }
+            Event::Mouse(mouse) => app.handle_mouse(mouse),
+            Event::Resize(width, height) => app.resize(width, height),
+            _ => {}
         }
     }
 
+    // Join the tick thread before tearing down the terminal
+    if let Some(mut source) = ticks.take() {
+        source.stop();
+    }
+
+    // Failing to remember the session shouldn't turn a clean exit into an error
+    let _ = app.save_config(&config_path);
+
     Ok(())
 }
 
 fn handle_normal_mode(app: &mut App, key: KeyEvent) -> bool {
+    // The help overlay swallows every key except the ones that close it
+    if app.show_help {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('?') => app.show_help = false,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return true,
+            _ => {}
+        }
+        return false;
+    }
+
+    // Digits build a count prefix; a leading 0 is reserved for line-start motion
+    if let KeyCode::Char(c) = key.code {
+        if let Some(digit) = c.to_digit(10) {
+            if digit != 0 || app.pending_count.is_some() {
+                app.push_count_digit(digit as usize);
+            }
+            return false;
+        }
+    }
+
+    // Any other key consumes the pending count (Esc simply discards it)
+    let count = app.pending_count.take().unwrap_or(1);
+
+    // Second half of a two-key motion such as gg
+    if app.pending_key.take() == Some('g') && key.code == KeyCode::Char('g') {
+        app.select_first_menu_item();
+        return false;
+    }
+
     match key.code {
         KeyCode::Char('q') => return true,
-        KeyCode::Char('j') | KeyCode::Down => app.next_menu_item(),
-        KeyCode::Char('k') | KeyCode::Up => app.prev_menu_item(),
-        KeyCode::Char(':') => app.mode = Mode::Command,
+        KeyCode::Char('j') | KeyCode::Down => app.next_menu_item(count),
+        KeyCode::Char('k') | KeyCode::Up => app.prev_menu_item(count),
+        KeyCode::Char('g') => app.pending_key = Some('g'),
+        KeyCode::Char('G') => app.select_last_menu_item(),
+        KeyCode::Char('?') => app.show_help = true,
+        KeyCode::Char('L') => app.toggle_live_refresh(),
+        KeyCode::Char('t') => app.theme = app.theme.toggled(),
+        KeyCode::Char(':') => {
+            app.mode = Mode::Command;
+            app.command_buffer.clear();
+            app.history_cursor = None;
+            app.status_message = None;
+        }
         KeyCode::Char('/') => {
             app.mode = Mode::Insert;
             app.search_query.clear();
+            app.content_search = app.selected_menu_item == MenuItem::TradingSystem;
         }
+        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.scroll_content(SCROLL_STEP)
+        }
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.scroll_content(-SCROLL_STEP)
+        }
+        KeyCode::PageDown => app.scroll_content(SCROLL_STEP),
+        KeyCode::PageUp => app.scroll_content(-SCROLL_STEP),
         KeyCode::Enter => {
             // Enter is handled by showing the selected item
         }
@@ -177,9 +1035,24 @@ fn handle_normal_mode(app: &mut App, key: KeyEvent) -> bool {
 
 fn handle_command_mode(app: &mut App, key: KeyEvent) -> bool {
     match key.code {
-        KeyCode::Char('q') => return true,
-        KeyCode::Esc => app.mode = Mode::Normal,
-        KeyCode::Enter => app.mode = Mode::Normal,
+        KeyCode::Esc => {
+            app.mode = Mode::Normal;
+            app.command_buffer.clear();
+        }
+        KeyCode::Enter => {
+            app.mode = Mode::Normal;
+            let command = std::mem::take(&mut app.command_buffer);
+            app.push_history(&command);
+            return app.execute_command(parse_command(&command));
+        }
+        KeyCode::Up => app.history_prev(),
+        KeyCode::Down => app.history_next(),
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return true,
+        KeyCode::Char(c) => app.command_buffer.push(c),
+        KeyCode::Backspace if app.command_buffer.is_empty() => app.mode = Mode::Normal,
+        KeyCode::Backspace => {
+            app.command_buffer.pop();
+        }
         _ => {}
     }
     false
@@ -202,13 +1075,20 @@ fn handle_insert_mode(app: &mut App, key: KeyEvent) -> bool {
 }
 
 fn draw_ui<W: Write>(stdout: &mut W, app: &App) -> io::Result<()> {
+    let theme = &app.theme;
     queue!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
 
     // Draw header
-    draw_header(stdout)?;
+    draw_header(stdout, theme, app.width())?;
+
+    if app.show_help {
+        draw_help(stdout, theme)?;
+        stdout.flush()?;
+        return Ok(());
+    }
 
     // Draw mode indicator
-    queue!(stdout, cursor::MoveTo(0, 2))?;
+    queue!(stdout, cursor::MoveTo(0, MENU_LABEL_ROW - 2))?;
     let mode_text = match app.mode {
         Mode::Normal => "-- NORMAL --",
         Mode::Command => "-- COMMAND --",
@@ -216,57 +1096,109 @@ fn draw_ui<W: Write>(stdout: &mut W, app: &App) -> io::Result<()> {
     };
     queue!(
         stdout,
-        SetForegroundColor(Color::Yellow),
+        SetForegroundColor(theme.mode),
         Print(format!("Mode: {}", mode_text)),
+        Print(app.pending_count.map(|count| format!("  {}", count)).unwrap_or_default()),
         ResetColor,
         Print("\n\n")
     )?;
 
+    // Draw command line and the last command error
+    if app.mode == Mode::Command {
+        queue!(
+            stdout,
+            SetForegroundColor(theme.command),
+            Print(format!(":{}", app.command_buffer)),
+            ResetColor,
+            Print("\n\n")
+        )?;
+    } else if let Some(ref message) = app.status_message {
+        queue!(
+            stdout,
+            SetForegroundColor(theme.error),
+            Print(format!("E: {}", message)),
+            ResetColor,
+            Print("\n\n")
+        )?;
+    }
+
     // Draw search query
     if !app.search_query.is_empty() || app.mode == Mode::Insert {
         queue!(
             stdout,
-            SetForegroundColor(Color::Cyan),
-            Print(format!("Search: {}", app.search_query)),
+            SetForegroundColor(theme.search),
+            Print(format!("{}: {}", if app.content_search { "Filter" } else { "Search" }, app.search_query)),
             ResetColor,
             Print("\n\n")
         )?;
     }
 
     // Draw menu
-    draw_menu(stdout, app)?;
+    draw_menu(stdout, app, theme)?;
 
     // Draw content for selected item
     queue!(stdout, Print("\n"))?;
-    draw_content(stdout, app)?;
+    draw_content(stdout, app, theme)?;
 
     // Draw footer
-    draw_footer(stdout)?;
+    draw_footer(stdout, theme, app.width())?;
 
     stdout.flush()?;
     Ok(())
 }
 
-fn draw_header<W: Write>(stdout: &mut W) -> io::Result<()> {
+fn draw_help<W: Write>(stdout: &mut W, theme: &Theme) -> io::Result<()> {
+    queue!(
+        stdout,
+        SetForegroundColor(theme.title),
+        Print("HELP - KEYBINDINGS\n\n"),
+        ResetColor
+    )?;
+
+    for (mode, bindings) in HELP_SECTIONS {
+        queue!(
+            stdout,
+            SetForegroundColor(theme.section),
+            Print(format!("{} MODE\n", mode)),
+            ResetColor
+        )?;
+        for (keys, description) in bindings.iter() {
+            queue!(stdout, Print(format!("  {:<22} {}\n", keys, description)))?;
+        }
+        queue!(stdout, Print("\n"))?;
+    }
+
+    queue!(
+        stdout,
+        SetForegroundColor(theme.muted),
+        Print("Press ? or Esc to close help\n"),
+        ResetColor
+    )?;
+    Ok(())
+}
+
+fn draw_header<W: Write>(stdout: &mut W, theme: &Theme, width: usize) -> io::Result<()> {
+    let inner = width.saturating_sub(2);
+    let title = format!("║{}║", truncate_to_width(&format!("{:^inner$}", HEADER_TITLE), inner));
     queue!(
         stdout,
-        SetBackgroundColor(Color::Blue),
-        SetForegroundColor(Color::White),
-        Print("╔═══════════════════════════════════════════════════════════════════════════════╗"),
+        SetBackgroundColor(theme.header_bg),
+        SetForegroundColor(theme.header_fg),
+        Print(border_line('╔', '═', '╗', width)),
         Print("\n"),
-        Print("║                         BET ARCHITECTURE SYSTEM                               ║"),
+        Print(title),
         Print("\n"),
-        Print("╚═══════════════════════════════════════════════════════════════════════════════╝"),
+        Print(border_line('╚', '═', '╝', width)),
         ResetColor,
         Print("\n")
     )?;
     Ok(())
 }
 
-fn draw_menu<W: Write>(stdout: &mut W, app: &App) -> io::Result<()> {
+fn draw_menu<W: Write>(stdout: &mut W, app: &App, theme: &Theme) -> io::Result<()> {
     queue!(
         stdout,
-        SetForegroundColor(Color::Green),
+        SetForegroundColor(theme.section),
         Print("MENU:\n"),
         ResetColor
     )?;
@@ -278,8 +1210,8 @@ fn draw_menu<W: Write>(stdout: &mut W, app: &App) -> io::Result<()> {
         if is_selected {
             queue!(
                 stdout,
-                SetBackgroundColor(Color::White),
-                SetForegroundColor(Color::Black),
+                SetBackgroundColor(theme.selected_bg),
+                SetForegroundColor(theme.selected_fg),
                 Print("  ▶ ")
             )?;
         } else {
@@ -293,6 +1225,7 @@ fn draw_menu<W: Write>(stdout: &mut W, app: &App) -> io::Result<()> {
             MenuItem::ADAG => "A-DAG (OCTOTREÉ, Topological Sort, Critical Path)",
             MenuItem::SwinTransformer => "SWIN Transformer (16 Heads, Grey Eyes, 600 Shades)",
             MenuItem::ElixirCheck => "Elixir Check (Erlang/OTP Guarantees)",
+            MenuItem::MarketData => "Market Data (Candlesticks from the Live Feed)",
             MenuItem::Quit => "Quit",
         };
 
@@ -308,98 +1241,715 @@ fn draw_menu<W: Write>(stdout: &mut W, app: &App) -> io::Result<()> {
     Ok(())
 }
 
-fn draw_content<W: Write>(stdout: &mut W, app: &App) -> io::Result<()> {
+// A chart row: the indent in the body colour, then each column in its candle's colour
+fn draw_candle_row<W: Write>(stdout: &mut W, line: &str, ups: &[bool], theme: &Theme, body_color: Color) -> io::Result<()> {
+    for (i, ch) in line.chars().enumerate() {
+        let color = match i.checked_sub(CANDLE_INDENT.len()).and_then(|column| ups.get(column)) {
+            Some(true) => theme.candle_up,
+            Some(false) => theme.candle_down,
+            None => body_color,
+        };
+        queue!(stdout, SetForegroundColor(color), Print(ch))?;
+    }
+    queue!(stdout, SetForegroundColor(body_color), Print("\n"))?;
+    Ok(())
+}
+
+fn draw_content<W: Write>(stdout: &mut W, app: &App, theme: &Theme) -> io::Result<()> {
     queue!(
         stdout,
-        SetForegroundColor(Color::Cyan),
-        Print("═══════════════════════════════════════════════════════════════════════════════\n"),
+        SetForegroundColor(theme.rule),
+        Print(format!("{}\n", "═".repeat(app.width()))),
         ResetColor
     )?;
 
-    match app.selected_menu_item {
-        MenuItem::TradingSystem => {
-            queue!(
-                stdout,
-                SetForegroundColor(Color::Yellow),
-                Print("TRADING SYSTEM\n"),
-                ResetColor,
-                Print(format!("{}\n", app.trading_system.display_summary()))
-            )?;
-        }
-        MenuItem::StormTopologies => {
-            queue!(
-                stdout,
-                SetForegroundColor(Color::Yellow),
-                Print("STORM TOPOLOGIES\n"),
-                ResetColor,
-                Print(format!("{}\n", app.storm.display()))
-            )?;
-        }
-        MenuItem::MonadLambda => {
-            queue!(
-                stdout,
-                SetForegroundColor(Color::Yellow),
-                Print("MONAD λ SYSTEM\n"),
-                ResetColor,
-                Print(format!("{}\n", demonstrate_monad_system()))
-            )?;
-        }
-        MenuItem::ADAG => {
-            queue!(
-                stdout,
-                SetForegroundColor(Color::Yellow),
-                Print("A-DAG (ACYCLIC DIRECTED ACYCLIC GRAPH)\n"),
-                ResetColor,
-                Print(format!("{}\n", app.trading_workflow.display())),
-                Print("Trading Workflow:\n")
-            )?;
-            if let Ok(order) = app.trading_workflow.get_execution_order() {
-                for (i, task) in order.iter().enumerate() {
-                    queue!(stdout, Print(format!("  {}. {}\n", i + 1, task)))?;
-                }
-            }
-        }
-        MenuItem::SwinTransformer => {
-            queue!(
-                stdout,
-                SetForegroundColor(Color::Yellow),
-                Print("SWIN TRANSFORMER\n"),
-                ResetColor,
-                Print(format!("{}\n", app.swin.display()))
-            )?;
-        }
-        MenuItem::ElixirCheck => {
-            let elixir_check = run_elixir_check();
-            queue!(
-                stdout,
-                SetForegroundColor(Color::Yellow),
-                Print("ELIXIR CHECK\n"),
-                ResetColor,
-                Print(format!("{}\n", elixir_check.display()))
-            )?;
-        }
-        MenuItem::Quit => {
-            queue!(
-                stdout,
-                SetForegroundColor(Color::Red),
-                Print("Press 'q' or Ctrl+C to exit\n"),
-                ResetColor
-            )?;
+    let title = match app.selected_menu_item {
+        MenuItem::TradingSystem => Some("TRADING SYSTEM"),
+        MenuItem::StormTopologies => Some("STORM TOPOLOGIES"),
+        MenuItem::MonadLambda => Some("MONAD λ SYSTEM"),
+        MenuItem::ADAG => Some("A-DAG (ACYCLIC DIRECTED ACYCLIC GRAPH)"),
+        MenuItem::SwinTransformer => Some("SWIN TRANSFORMER"),
+        MenuItem::ElixirCheck => Some("ELIXIR CHECK"),
+        MenuItem::MarketData => Some("MARKET DATA"),
+        MenuItem::Quit => None,
+    };
+    if let Some(title) = title {
+        queue!(
+            stdout,
+            SetForegroundColor(theme.title),
+            Print(format!("{}\n", title)),
+            ResetColor
+        )?;
+    }
+
+    let body_color = match app.selected_menu_item {
+        MenuItem::Quit => theme.error,
+        _ => theme.body,
+    };
+
+    // Render only the scrolled, visible slice of the content
+    let lines = app.content_lines();
+    let visible = app.visible_content_rows();
+    let scroll = clamp_scroll(app.content_scroll, lines.len(), visible) as usize;
+    let candle_columns: Vec<Option<Vec<bool>>> = if app.selected_menu_item == MenuItem::MarketData {
+        app.market_lines().into_iter().map(|(_, ups)| ups).collect()
+    } else {
+        Vec::new()
+    };
+    queue!(stdout, SetForegroundColor(body_color))?;
+    for (i, line) in lines.iter().enumerate().skip(scroll).take(visible) {
+        let line = truncate_to_width(line, app.width());
+        match candle_columns.get(i) {
+            Some(Some(ups)) => draw_candle_row(stdout, &line, ups, theme, body_color)?,
+            _ => queue!(stdout, Print(format!("{}\n", line)))?,
         }
     }
+    queue!(stdout, ResetColor)?;
+
+    if lines.len() > visible {
+        queue!(
+            stdout,
+            SetForegroundColor(theme.muted),
+            Print(format!(
+                "-- lines {}-{} of {} (Ctrl-D/Ctrl-U to scroll) --\n",
+                scroll + 1,
+                (scroll + visible).min(lines.len()),
+                lines.len()
+            )),
+            ResetColor
+        )?;
+    }
 
     Ok(())
 }
 
-fn draw_footer<W: Write>(stdout: &mut W) -> io::Result<()> {
+fn draw_footer<W: Write>(stdout: &mut W, theme: &Theme, width: usize) -> io::Result<()> {
+    let keys = "Keys: j/k or ↑/↓ (navigate) | gg/G (top/bottom) | Ctrl-D/U (scroll) | / (search) | : (command) | ? (help) | q (quit)";
+    let accessibility = "Screen Reader: Menu items are numbered and labeled for accessibility";
     queue!(
         stdout,
-        SetForegroundColor(Color::DarkGrey),
+        SetForegroundColor(theme.muted),
         Print("\n"),
-        Print("─────────────────────────────────────────────────────────────────────────────\n"),
-        Print("Keys: j/k or ↑/↓ (navigate) | / (search) | : (command) | Enter (select) | q (quit)\n"),
-        Print("Screen Reader: Menu items are numbered and labeled for accessibility\n"),
+        Print(format!("{}\n", "─".repeat(width))),
+        Print(format!("{}\n", truncate_to_width(keys, width))),
+        Print(format!("{}\n", truncate_to_width(accessibility, width))),
         ResetColor
     )?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bet_architecture::signals::{SignalType, TradingSignal};
+    use bet_architecture::snapshot::Snapshot;
+
+    #[test]
+    fn test_parse_command_quit() {
+        assert_eq!(parse_command(":quit"), CommandAction::Quit);
+        assert_eq!(parse_command(":q"), CommandAction::Quit);
+        assert_eq!(parse_command("quit"), CommandAction::Quit);
+    }
+
+    #[test]
+    fn test_parse_command_refresh_and_goto() {
+        assert_eq!(parse_command(":refresh"), CommandAction::Refresh);
+        assert_eq!(parse_command(":goto adag"), CommandAction::Goto(MenuItem::ADAG));
+        assert_eq!(parse_command(":goto Swin"), CommandAction::Goto(MenuItem::SwinTransformer));
+        assert_eq!(parse_command(":goto trading system"), CommandAction::Goto(MenuItem::TradingSystem));
+        assert_eq!(parse_command(":goto Elixir   Check"), CommandAction::Goto(MenuItem::ElixirCheck));
+        assert_eq!(parse_command(":goto market"), CommandAction::Goto(MenuItem::MarketData));
+        assert!(matches!(parse_command(":goto elixircheck"), CommandAction::Unknown(_)));
+        assert!(matches!(parse_command(":goto nowhere"), CommandAction::Unknown(_)));
+        assert!(matches!(parse_command(":goto"), CommandAction::Unknown(_)));
+    }
+
+    #[test]
+    fn test_fuzzy_score_matches_subsequence() {
+        assert!(fuzzy_score("stf", "SwinTransformer").is_some());
+        assert!(fuzzy_score("swin trans", "SwinTransformer").is_some());
+        assert!(fuzzy_score("STF", "SwinTransformer").is_some());
+        assert!(fuzzy_score("xyz", "SwinTransformer").is_none());
+        assert!(fuzzy_score("fts", "SwinTransformer").is_none());
+    }
+
+    #[test]
+    fn test_fuzzy_score_prefers_word_starts() {
+        let hump = fuzzy_score("st", "SwinTransformer").unwrap();
+        let scattered = fuzzy_score("st", "TradingSystem").unwrap();
+        assert!(hump > scattered);
+    }
+
+    #[test]
+    fn test_menu_search_ranks_fuzzy_matches() {
+        let mut app = App::new();
+        app.search_query = "xyz".to_string();
+        assert!(app.get_menu_items().is_empty());
+
+        app.search_query = "stf".to_string();
+        assert_eq!(app.get_menu_items()[0], MenuItem::SwinTransformer);
+
+        app.search_query.clear();
+        assert_eq!(app.get_menu_items().len(), 8);
+    }
+
+    #[test]
+    fn test_clamp_scroll() {
+        assert_eq!(clamp_scroll(0, 50, 10), 0);
+        assert_eq!(clamp_scroll(25, 50, 10), 25);
+        assert_eq!(clamp_scroll(45, 50, 10), 40);
+        assert_eq!(clamp_scroll(u16::MAX, 50, 10), 40);
+        assert_eq!(clamp_scroll(5, 3, 10), 0);
+    }
+
+    #[test]
+    fn test_scroll_resets_on_menu_change() {
+        let mut app = App::new();
+        app.content_scroll = 3;
+        app.next_menu_item(1);
+        assert_eq!(app.content_scroll, 0);
+    }
+
+    #[test]
+    fn test_scroll_content_never_exceeds_content() {
+        let mut app = App::new();
+        let max = app.content_lines().len().saturating_sub(app.visible_content_rows()) as u16;
+        for _ in 0..20 {
+            app.scroll_content(SCROLL_STEP);
+        }
+        assert_eq!(app.content_scroll, max);
+        app.scroll_content(-1000);
+        assert_eq!(app.content_scroll, 0);
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_next_menu_item_with_count_wraps() {
+        let mut app = App::new();
+        app.next_menu_item(3);
+        assert_eq!(app.selected_menu_item, MenuItem::ADAG);
+        app.next_menu_item(3);
+        assert_eq!(app.selected_menu_item, MenuItem::MarketData);
+        app.next_menu_item(3);
+        assert_eq!(app.selected_menu_item, MenuItem::StormTopologies);
+        app.prev_menu_item(3);
+        assert_eq!(app.selected_menu_item, MenuItem::MarketData);
+    }
+
+    #[test]
+    fn test_count_prefix_applies_to_motion() {
+        let mut app = App::new();
+        handle_normal_mode(&mut app, key(KeyCode::Char('3')));
+        assert_eq!(app.pending_count, Some(3));
+        handle_normal_mode(&mut app, key(KeyCode::Char('j')));
+        assert_eq!(app.selected_menu_item, MenuItem::ADAG);
+        assert_eq!(app.pending_count, None);
+    }
+
+    #[test]
+    fn test_count_prefix_leading_zero_and_esc() {
+        let mut app = App::new();
+        handle_normal_mode(&mut app, key(KeyCode::Char('0')));
+        assert_eq!(app.pending_count, None);
+
+        handle_normal_mode(&mut app, key(KeyCode::Char('1')));
+        handle_normal_mode(&mut app, key(KeyCode::Char('0')));
+        assert_eq!(app.pending_count, Some(10));
+        handle_normal_mode(&mut app, key(KeyCode::Esc));
+        assert_eq!(app.pending_count, None);
+        assert_eq!(app.selected_menu_item, MenuItem::TradingSystem);
+    }
+
+    #[test]
+    fn test_select_first_and_last_full_menu() {
+        let mut app = App::new();
+        app.select_last_menu_item();
+        assert_eq!(app.selected_menu_item, MenuItem::Quit);
+        app.select_first_menu_item();
+        assert_eq!(app.selected_menu_item, MenuItem::TradingSystem);
+    }
+
+    #[test]
+    fn test_select_first_and_last_filtered_menu() {
+        let mut app = App::new();
+        app.search_query = "s".to_string();
+        let items = app.get_menu_items();
+        app.select_last_menu_item();
+        assert_eq!(app.selected_menu_item, *items.last().unwrap());
+        assert_ne!(app.selected_menu_item, MenuItem::Quit);
+        app.select_first_menu_item();
+        assert_eq!(app.selected_menu_item, items[0]);
+    }
+
+    #[test]
+    fn test_gg_and_shift_g_keys() {
+        let mut app = App::new();
+        handle_normal_mode(&mut app, key(KeyCode::Char('G')));
+        assert_eq!(app.selected_menu_item, MenuItem::Quit);
+
+        handle_normal_mode(&mut app, key(KeyCode::Char('g')));
+        assert_eq!(app.selected_menu_item, MenuItem::Quit);
+        handle_normal_mode(&mut app, key(KeyCode::Char('g')));
+        assert_eq!(app.selected_menu_item, MenuItem::TradingSystem);
+
+        // A lone g followed by another motion does not jump
+        handle_normal_mode(&mut app, key(KeyCode::Char('g')));
+        handle_normal_mode(&mut app, key(KeyCode::Char('j')));
+        assert_eq!(app.selected_menu_item, MenuItem::StormTopologies);
+        assert_eq!(app.pending_key, None);
+    }
+
+    #[test]
+    fn test_help_overlay_toggle() {
+        let mut app = App::new();
+        handle_normal_mode(&mut app, key(KeyCode::Char('?')));
+        assert!(app.show_help);
+        handle_normal_mode(&mut app, key(KeyCode::Char('?')));
+        assert!(!app.show_help);
+
+        handle_normal_mode(&mut app, key(KeyCode::Char('?')));
+        handle_normal_mode(&mut app, key(KeyCode::Esc));
+        assert!(!app.show_help);
+    }
+
+    #[test]
+    fn test_help_overlay_ignores_navigation() {
+        let mut app = App::new();
+        app.show_help = true;
+        assert!(!handle_normal_mode(&mut app, key(KeyCode::Char('j'))));
+        assert!(!handle_normal_mode(&mut app, key(KeyCode::Char('q'))));
+        assert_eq!(app.selected_menu_item, MenuItem::TradingSystem);
+        assert!(app.show_help);
+    }
+
+    #[test]
+    fn test_session_config_round_trip() {
+        let path = std::env::temp_dir().join("bet_session_round_trip.json");
+        let path = path.to_str().unwrap();
+        let config = SessionConfig {
+            selected_menu_item: MenuItem::SwinTransformer,
+            initial_capital: 250_000.0,
+        };
+        config.save(path).unwrap();
+        assert_eq!(SessionConfig::load(path), config);
+
+        let app = App::load_config(path);
+        assert_eq!(app.selected_menu_item, MenuItem::SwinTransformer);
+        assert_eq!(app.trading_system.pnl_calc.initial_capital(), 250_000.0);
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_session_config_falls_back_to_defaults() {
+        assert_eq!(SessionConfig::load("/nonexistent/bet_session.json"), SessionConfig::default());
+
+        let path = std::env::temp_dir().join("bet_session_corrupt.json");
+        let path = path.to_str().unwrap();
+        fs::write(path, "{ not json").unwrap();
+        assert_eq!(SessionConfig::load(path), SessionConfig::default());
+
+        fs::write(path, r#"{"selected_menu_item":"ADAG","initial_capital":-5.0}"#).unwrap();
+        assert_eq!(SessionConfig::load(path), SessionConfig::default());
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_live_refresh_tick_moves_prices() {
+        let mut app = App::new();
+        app.toggle_live_refresh();
+        assert!(app.live_refresh);
+        assert!(!app.trading_system.positions.is_empty());
+
+        let before = app.trading_system.get_portfolio_value();
+        app.on_tick();
+        assert_eq!(app.tick, 1);
+        assert_ne!(app.trading_system.get_portfolio_value(), before);
+
+        app.toggle_live_refresh();
+        assert!(!app.live_refresh);
+    }
+
+    #[test]
+    fn test_produce_ticks_stops_after_limit() {
+        let (tx, rx) = mpsc::channel();
+        let (_stop, stop_rx) = mpsc::channel();
+        produce_ticks(tx, stop_rx, Duration::from_millis(1), Some(3));
+
+        let received: Vec<TickEvent> = rx.iter().collect();
+        assert_eq!(
+            received,
+            vec![TickEvent::PriceUpdate(1), TickEvent::PriceUpdate(2), TickEvent::PriceUpdate(3)]
+        );
+    }
+
+    #[test]
+    fn test_tick_source_stop_joins_promptly() {
+        let mut source = TickSource::start(Duration::from_secs(60));
+        let started = std::time::Instant::now();
+        source.stop();
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(source.handle.is_none());
+    }
+
+    #[test]
+    fn test_sync_tick_source_follows_live_mode() {
+        let mut app = App::new();
+        let mut source = None;
+        sync_tick_source(&app, &mut source);
+        assert!(source.is_none());
+
+        app.toggle_live_refresh();
+        app.refresh_interval = Duration::from_millis(5);
+        sync_tick_source(&app, &mut source);
+        assert_eq!(source.as_ref().map(|s| s.interval), Some(Duration::from_millis(5)));
+        let tick = source.as_ref().unwrap().ticks.recv_timeout(Duration::from_secs(1));
+        assert_eq!(tick, Ok(TickEvent::PriceUpdate(1)));
+
+        app.toggle_live_refresh();
+        sync_tick_source(&app, &mut source);
+        assert!(source.is_none());
+    }
+
+    fn click(row: u16) -> MouseEvent {
+        MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: 10,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    #[test]
+    fn test_border_line() {
+        assert_eq!(border_line('╔', '═', '╗', 5), "╔═══╗");
+        assert_eq!(border_line('╚', '═', '╝', 2), "╚╝");
+        assert_eq!(border_line('╔', '═', '╗', 1), "═");
+        assert_eq!(border_line('╔', '═', '╗', 0), "");
+        assert_eq!(border_line('╔', '═', '╗', 40).chars().count(), 40);
+    }
+
+    #[test]
+    fn test_sparkline() {
+        let line = sparkline(&[1.0, 5.0, 3.0, 9.0, 2.0], 10);
+        assert_eq!(line.chars().count(), 5);
+        assert_eq!(line.chars().nth(3), Some('█'));
+        assert_eq!(line.chars().next(), Some('▁'));
+
+        let long: Vec<f64> = (0..100).map(|i| i as f64).collect();
+        let line = sparkline(&long, 20);
+        assert_eq!(line.chars().count(), 20);
+        assert_eq!(line.chars().last(), Some('█'));
+
+        assert_eq!(sparkline(&[], 10), "");
+        assert_eq!(sparkline(&[4.0; 3], 10), "▄▄▄");
+    }
+
+    #[test]
+    fn test_trading_panel_shows_equity_sparkline() {
+        let mut app = App::new();
+        app.trading_system.simulate_tick(1);
+        let lines = app.content_lines();
+        assert!(lines.iter().any(|line| line.starts_with("  Equity: ")));
+    }
+
+    fn position(symbol: &str, quantity: f64) -> Position {
+        Position {
+            symbol: symbol.to_string(),
+            quantity,
+            avg_price: 100.0,
+            current_price: 110.0,
+        }
+    }
+
+    #[test]
+    fn test_filter_positions_by_symbol() {
+        let positions = vec![position("MRNA", 10.0), position("BNTX", 5.0), position("MRK", 2.0)];
+        let symbols = |query: &str| -> Vec<String> {
+            filter_positions(&positions, query).iter().map(|p| p.symbol.clone()).collect()
+        };
+        assert_eq!(symbols("mr"), vec!["MRNA", "MRK"]);
+        assert_eq!(symbols("NTX"), vec!["BNTX"]);
+        assert_eq!(symbols("").len(), 3);
+        assert!(symbols("ZZZ").is_empty());
+    }
+
+    #[test]
+    fn test_slash_on_trading_panel_filters_positions() {
+        let mut app = App::new();
+        app.trading_system.add_position(position("MRNA", 10.0));
+        app.trading_system.add_position(position("BNTX", 5.0));
+
+        handle_normal_mode(&mut app, key(KeyCode::Char('/')));
+        for c in "bn".chars() {
+            handle_insert_mode(&mut app, key(KeyCode::Char(c)));
+        }
+        // The menu is left alone while the query targets the content
+        assert_eq!(app.get_menu_items().len(), 8);
+        let lines = app.content_lines();
+        assert!(lines.iter().any(|line| line.trim_start().starts_with("BNTX")));
+        assert!(!lines.iter().any(|line| line.trim_start().starts_with("MRNA")));
+
+        handle_insert_mode(&mut app, key(KeyCode::Char('x')));
+        assert!(app.content_lines().iter().any(|line| line.trim() == "no matches"));
+
+        handle_insert_mode(&mut app, key(KeyCode::Esc));
+        assert!(app.search_query.is_empty());
+    }
+
+    fn candle(open: f64, high: f64, low: f64, close: f64) -> OHLCV {
+        OHLCV { open, high, low, close, volume: 0, timestamp: 0 }
+    }
+
+    #[test]
+    fn test_render_candles() {
+        let bars = vec![
+            candle(10.0, 12.0, 9.0, 11.0),
+            candle(11.0, 16.0, 10.0, 15.0),
+            candle(15.0, 15.2, 12.0, 13.0),
+        ];
+        let glyphs = UNICODE_CANDLES;
+        let chart = render_candles(&bars, 8, glyphs);
+        let rows: Vec<Vec<char>> = chart.lines().map(|line| line.chars().collect()).collect();
+        assert_eq!(rows.len(), 8);
+        assert!(rows.iter().all(|row| row.len() == bars.len()));
+        // Only the bar holding the overall high reaches the top row, and only by its wick
+        assert_eq!(rows[0], vec![' ', glyphs.wick, ' ']);
+        // Bottom row is the overall low of the first bar
+        assert_eq!(rows[7][0], glyphs.wick);
+        assert!(rows.iter().any(|row| row[2] == glyphs.down));
+        assert!(rows.iter().any(|row| row[1] == glyphs.up));
+    }
+
+    #[test]
+    fn test_render_candles_single_flat_and_ascii() {
+        let single = render_candles(&[candle(10.0, 14.0, 8.0, 12.0)], 4, UNICODE_CANDLES);
+        assert_eq!(single.lines().count(), 4);
+        assert!(single.lines().all(|line| line.chars().count() == 1));
+
+        let flat = render_candles(&vec![candle(5.0, 5.0, 5.0, 5.0); 3], 5, UNICODE_CANDLES);
+        let rows: Vec<&str> = flat.lines().collect();
+        assert_eq!(rows[2], UNICODE_CANDLES.up.to_string().repeat(3));
+        assert!(rows.iter().enumerate().all(|(i, row)| i == 2 || row.trim().is_empty()));
+        assert_eq!(render_candles(&[], 5, UNICODE_CANDLES), "");
+
+        let ascii = render_candles(&[candle(10.0, 14.0, 8.0, 12.0), candle(12.0, 13.0, 9.0, 10.0)], 4, ASCII_CANDLES);
+        assert!(ascii.is_ascii());
+        assert!(ascii.contains('#') && ascii.contains('=') && ascii.contains('|'));
+    }
+
+    #[test]
+    fn test_market_data_panel_at_tiny_width() {
+        let mut app = App::new();
+        app.toggle_live_refresh();
+        for _ in 0..CANDLE_TICKS {
+            app.on_tick();
+        }
+        app.select_menu_item(MenuItem::MarketData);
+        for width in 0..=(CANDLE_INDENT.len() as u16) {
+            app.resize(width, 40);
+            let lines = app.market_lines();
+            assert!(lines.iter().all(|(_, ups)| ups.is_none()));
+            assert!(lines.iter().any(|(line, _)| line.contains("too narrow")));
+        }
+        app.resize(CANDLE_INDENT.len() as u16 + 1, 40);
+        assert!(app.market_lines().iter().any(|(_, ups)| ups.as_ref().is_some_and(|ups| ups.len() == 1)));
+    }
+
+    #[test]
+    fn test_candle_glyphs_for_locale() {
+        assert_eq!(candle_glyphs_for_locale(Some("en_US.UTF-8")), UNICODE_CANDLES);
+        assert_eq!(candle_glyphs_for_locale(Some("C.utf8")), UNICODE_CANDLES);
+        assert_eq!(candle_glyphs_for_locale(Some("C")), ASCII_CANDLES);
+        assert_eq!(candle_glyphs_for_locale(Some("en_US.ISO-8859-1")), ASCII_CANDLES);
+        assert_eq!(candle_glyphs_for_locale(None), UNICODE_CANDLES);
+    }
+
+    #[test]
+    fn test_market_data_panel_charts_feed_bars() {
+        let mut app = App::new();
+        app.candle_glyphs = ASCII_CANDLES;
+        app.select_menu_item(MenuItem::MarketData);
+        assert!(app.content_lines()[0].contains("No bars yet"));
+
+        app.toggle_live_refresh();
+        for _ in 0..(CANDLE_TICKS * 2) {
+            app.on_tick();
+        }
+        let symbols = app.trading_system.market_feed.bar_symbols().len();
+        let lines = app.market_lines();
+        let chart_rows: Vec<&Vec<bool>> = lines.iter().filter_map(|(_, ups)| ups.as_ref()).collect();
+        assert_eq!(chart_rows.len(), symbols * CANDLE_HEIGHT);
+        // Ten one-tick bars resample into two candles per symbol
+        assert!(chart_rows.iter().all(|ups| ups.len() == 2));
+        assert_eq!(app.content_lines().len(), lines.len());
+        assert!(lines.iter().filter(|(_, ups)| ups.is_some()).all(|(line, _)| line.is_ascii()));
+    }
+
+    #[test]
+    fn test_truncate_to_width() {
+        assert_eq!(truncate_to_width("Edison⚡ power", 7), "Edison⚡");
+        assert_eq!(truncate_to_width("short", 80), "short");
+    }
+
+    #[test]
+    fn test_resize_updates_layout() {
+        let mut app = App::new();
+        app.resize(40, 12);
+        assert_eq!(app.terminal_size, (40, 12));
+        assert_eq!(app.width(), 40);
+        let small = app.visible_content_rows();
+        app.resize(40, 60);
+        assert!(app.visible_content_rows() > small);
+
+        let mut out = Vec::new();
+        draw_header(&mut out, &app.theme, 30).unwrap();
+        let rendered = String::from_utf8(out).unwrap();
+        assert!(rendered.contains(&border_line('╔', '═', '╗', 30)));
+        assert!(rendered.contains("BET ARCHITECTURE SYSTEM"));
+    }
+
+    #[test]
+    fn test_menu_index_at_row() {
+        assert_eq!(menu_index_at_row(5, 5, 7), Some(0));
+        assert_eq!(menu_index_at_row(11, 5, 7), Some(6));
+        assert_eq!(menu_index_at_row(12, 5, 7), None);
+        assert_eq!(menu_index_at_row(4, 5, 7), None);
+    }
+
+    #[test]
+    fn test_mouse_click_selects_menu_item() {
+        let mut app = App::new();
+        assert_eq!(app.menu_top_row(), 5);
+        app.handle_mouse(click(7));
+        assert_eq!(app.selected_menu_item, MenuItem::all()[2]);
+
+        // A search line pushes the menu down, and results are re-ranked
+        app.search_query = "swin".to_string();
+        assert_eq!(app.menu_top_row(), 7);
+        app.handle_mouse(click(7));
+        assert_eq!(app.selected_menu_item, MenuItem::SwinTransformer);
+
+        // Clicking outside the menu changes nothing
+        app.handle_mouse(click(0));
+        assert_eq!(app.selected_menu_item, MenuItem::SwinTransformer);
+    }
+
+    #[test]
+    fn test_parse_capital() {
+        assert_eq!(parse_capital("250000"), Ok(250_000.0));
+        assert_eq!(parse_capital("$1,000,000"), Ok(1_000_000.0));
+        assert_eq!(parse_capital("2.5e6"), Ok(2_500_000.0));
+        assert!(parse_capital("-500").is_err());
+        assert!(parse_capital("0").is_err());
+        assert!(parse_capital("lots").is_err());
+        assert!(parse_capital("inf").is_err());
+        assert!(parse_capital("NaN").is_err());
+    }
+
+    #[test]
+    fn test_capital_command_preserves_positions() {
+        let mut app = App::new();
+        app.toggle_live_refresh();
+        let positions = app.trading_system.positions.len();
+        app.trading_system.pnl_calc.add_realized_pnl(42.0);
+        app.trading_system.add_signal(TradingSignal::new(SignalType::Buy, "MRNA", 0.9, "Breakout"));
+        let curve_len = app.trading_system.equity_curve().len();
+
+        assert!(!run_command(&mut app, "capital 500000"));
+        assert_eq!(app.status_message, None);
+        assert_eq!(app.trading_system.pnl_calc.initial_capital(), 500_000.0);
+        assert_eq!(app.trading_system.positions.len(), positions);
+        assert_eq!(app.trading_system.pnl_calc.snapshot(), 42.0);
+        assert_eq!(app.trading_system.equity_curve().len(), curve_len);
+        // Signal ids keep counting instead of restarting under the carried-over signals
+        let next = app.trading_system.add_signal(TradingSignal::new(SignalType::Sell, "MRNA", 0.5, "Fade"));
+        assert_eq!(next, 2);
+
+        run_command(&mut app, "capital -5");
+        assert!(app.status_message.as_deref().unwrap().contains("positive"));
+        assert_eq!(app.trading_system.pnl_calc.initial_capital(), 500_000.0);
+    }
+
+    #[test]
+    fn test_parse_command_interval() {
+        assert_eq!(
+            parse_command(":interval 250"),
+            CommandAction::SetInterval(Duration::from_millis(250))
+        );
+        assert!(matches!(parse_command(":interval 0"), CommandAction::Unknown(_)));
+        assert!(matches!(parse_command(":interval fast"), CommandAction::Unknown(_)));
+    }
+
+    #[test]
+    fn test_themes_differ() {
+        let dark = Theme::dark();
+        let light = Theme::light();
+        assert_ne!(dark.header_bg, light.header_bg);
+        assert_eq!(dark.toggled(), light);
+        assert_eq!(light.toggled(), dark);
+    }
+
+    #[test]
+    fn test_theme_toggle_key() {
+        let mut app = App::new();
+        assert_eq!(app.theme, Theme::dark());
+        handle_normal_mode(&mut app, key(KeyCode::Char('t')));
+        assert_eq!(app.theme, Theme::light());
+    }
+
+    fn run_command(app: &mut App, command: &str) -> bool {
+        handle_normal_mode(app, key(KeyCode::Char(':')));
+        for c in command.chars() {
+            handle_command_mode(app, key(KeyCode::Char(c)));
+        }
+        handle_command_mode(app, key(KeyCode::Enter))
+    }
+
+    #[test]
+    fn test_command_history_recall() {
+        let mut app = App::new();
+        assert!(!run_command(&mut app, "refresh"));
+        assert!(run_command(&mut app, "quit"));
+
+        handle_normal_mode(&mut app, key(KeyCode::Char(':')));
+        handle_command_mode(&mut app, key(KeyCode::Up));
+        assert_eq!(app.command_buffer, "quit");
+        handle_command_mode(&mut app, key(KeyCode::Up));
+        assert_eq!(app.command_buffer, "refresh");
+        handle_command_mode(&mut app, key(KeyCode::Up));
+        assert_eq!(app.command_buffer, "refresh");
+
+        handle_command_mode(&mut app, key(KeyCode::Down));
+        assert_eq!(app.command_buffer, "quit");
+        handle_command_mode(&mut app, key(KeyCode::Down));
+        assert_eq!(app.command_buffer, "");
+    }
+
+    #[test]
+    fn test_command_history_dedups_consecutive() {
+        let mut app = App::new();
+        run_command(&mut app, "refresh");
+        run_command(&mut app, "refresh");
+        run_command(&mut app, "goto adag");
+        run_command(&mut app, "refresh");
+        assert_eq!(app.command_history, vec!["refresh", "goto adag", "refresh"]);
+    }
+
+    #[test]
+    fn test_execute_unknown_command_sets_error() {
+        let mut app = App::new();
+        assert!(!app.execute_command(parse_command(":frobnicate")));
+        assert!(app.status_message.unwrap().contains("frobnicate"));
+    }
+
+    #[test]
+    fn test_execute_goto_selects_item() {
+        let mut app = App::new();
+        assert!(!app.execute_command(CommandAction::Goto(MenuItem::ElixirCheck)));
+        assert_eq!(app.selected_menu_item, MenuItem::ElixirCheck);
+        assert!(app.execute_command(CommandAction::Quit));
+    }
+}
@@ -1,8 +1,12 @@
 // Market Data
 // Real-time and historical market data structures
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
+use crate::clock::Clock;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Quote {
     pub symbol: String,
@@ -21,6 +25,11 @@ impl Quote {
     pub fn mid_price(&self) -> f64 {
         (self.bid + self.ask) / 2.0
     }
+
+    // Older than max_age_ms by the clock; timestamps are epoch milliseconds
+    pub fn is_stale(&self, clock: &dyn Clock, max_age_ms: u64) -> bool {
+        clock.now_ms().saturating_sub(self.timestamp) > max_age_ms
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,8 +42,62 @@ pub struct OHLCV {
     pub timestamp: u64,
 }
 
+impl OHLCV {
+    pub fn new(open: f64, high: f64, low: f64, close: f64, volume: u64, timestamp: u64) -> Result<OHLCV, String> {
+        let bar = OHLCV {
+            open,
+            high,
+            low,
+            close,
+            volume,
+            timestamp,
+        };
+        if high < low {
+            return Err(format!("Invalid bar: high {} below low {}", high, low));
+        }
+        if !(low..=high).contains(&open) {
+            return Err(format!("Invalid bar: open {} outside [{}, {}]", open, low, high));
+        }
+        if !(low..=high).contains(&close) {
+            return Err(format!("Invalid bar: close {} outside [{}, {}]", close, low, high));
+        }
+        Ok(bar)
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.high >= self.low
+            && (self.low..=self.high).contains(&self.open)
+            && (self.low..=self.high).contains(&self.close)
+    }
+}
+
+// Group consecutive bars into coarser ones; a trailing partial group still yields a bar
+pub fn resample(bars: &[OHLCV], factor: usize) -> Vec<OHLCV> {
+    if factor == 0 {
+        return Vec::new();
+    }
+    bars.chunks(factor)
+        .map(|group| OHLCV {
+            open: group[0].open,
+            high: group.iter().map(|b| b.high).fold(f64::NEG_INFINITY, f64::max),
+            low: group.iter().map(|b| b.low).fold(f64::INFINITY, f64::min),
+            close: group[group.len() - 1].close,
+            volume: group.iter().map(|b| b.volume).sum(),
+            timestamp: group[0].timestamp,
+        })
+        .collect()
+}
+
+// Subscribing to this symbol receives every quote
+pub const ALL_SYMBOLS: &str = "*";
+
+// Send so a feed with subscribers can still move between threads
+pub type QuoteCallback = Box<dyn FnMut(&Quote) + Send>;
+
 pub struct MarketDataFeed {
     quotes: Vec<Quote>,
+    bars: HashMap<String, Vec<OHLCV>>,
+    subscribers: HashMap<String, Vec<QuoteCallback>>,
 }
 
 impl Default for MarketDataFeed {
@@ -45,20 +108,91 @@ impl Default for MarketDataFeed {
 
 impl MarketDataFeed {
     pub fn new() -> Self {
-        MarketDataFeed { quotes: Vec::new() }
+        MarketDataFeed {
+            quotes: Vec::new(),
+            bars: HashMap::new(),
+            subscribers: HashMap::new(),
+        }
     }
 
+    // Call `cb` for each new quote on `symbol`, or on every symbol for ALL_SYMBOLS
+    pub fn subscribe(&mut self, symbol: &str, cb: QuoteCallback) {
+        self.subscribers.entry(symbol.to_string()).or_default().push(cb);
+    }
+
+    // Symbol subscribers are notified before wildcard ones, each in subscription order
     pub fn add_quote(&mut self, quote: Quote) {
+        for key in [quote.symbol.as_str(), ALL_SYMBOLS] {
+            if let Some(callbacks) = self.subscribers.get_mut(key) {
+                for cb in callbacks.iter_mut() {
+                    cb(&quote);
+                }
+            }
+        }
         self.quotes.push(quote);
     }
 
+    pub fn add_bar(&mut self, symbol: &str, bar: OHLCV) {
+        self.bars.entry(symbol.to_string()).or_default().push(bar);
+    }
+
+    // Bars for the symbol in arrival order; empty if none were recorded
+    pub fn bars(&self, symbol: &str) -> &[OHLCV] {
+        self.bars.get(symbol).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    // Symbols with at least one bar, sorted
+    pub fn bar_symbols(&self) -> Vec<&str> {
+        let mut symbols: Vec<&str> = self.bars.keys().map(String::as_str).collect();
+        symbols.sort();
+        symbols
+    }
+
     pub fn latest_quote(&self, symbol: &str) -> Option<&Quote> {
         self.quotes.iter().rev().find(|q| q.symbol == symbol)
     }
 
+    // Latest quote for the symbol, unless it has gone stale
+    pub fn fresh_quote(&self, symbol: &str, clock: &dyn Clock, max_age_ms: u64) -> Option<&Quote> {
+        self.latest_quote(symbol).filter(|q| !q.is_stale(clock, max_age_ms))
+    }
+
     pub fn get_all_quotes(&self) -> &[Quote] {
         &self.quotes
     }
+
+    pub fn best_bid(&self, symbol: &str) -> Option<f64> {
+        self.latest_quote(symbol).map(|q| q.bid)
+    }
+
+    pub fn best_ask(&self, symbol: &str) -> Option<f64> {
+        self.latest_quote(symbol).map(|q| q.ask)
+    }
+
+    // (min, mean, max) bid/ask spread across every stored quote for the symbol
+    pub fn spread_stats(&self, symbol: &str) -> Option<(f64, f64, f64)> {
+        let spreads: Vec<f64> = self.quotes_for(symbol).map(|q| q.spread()).collect();
+        if spreads.is_empty() {
+            return None;
+        }
+        let min = spreads.iter().copied().fold(f64::MAX, f64::min);
+        let max = spreads.iter().copied().fold(f64::MIN, f64::max);
+        let mean = spreads.iter().sum::<f64>() / spreads.len() as f64;
+        Some((min, mean, max))
+    }
+
+    // Lazily stream a symbol's last prices in arrival order, ready to collect for indicators
+    pub fn closes<'a>(&'a self, symbol: &'a str) -> impl Iterator<Item = f64> + 'a {
+        self.quotes_for(symbol).map(|q| q.last)
+    }
+
+    pub fn volumes<'a>(&'a self, symbol: &'a str) -> impl Iterator<Item = u64> + 'a {
+        self.quotes_for(symbol).map(|q| q.volume)
+    }
+
+    fn quotes_for<'a>(&'a self, symbol: &'a str) -> impl Iterator<Item = &'a Quote> + 'a {
+        self.quotes.iter().filter(move |q| q.symbol == symbol)
+    }
 }
 
 #[cfg(test)]
@@ -145,4 +279,182 @@ mod tests {
         let feed = MarketDataFeed::new();
         assert!(feed.latest_quote("NONEXISTENT").is_none());
     }
+
+    #[test]
+    fn test_market_data_feed_closes_and_volumes() {
+        let mut feed = MarketDataFeed::new();
+        for (symbol, last, volume) in [("MRNA", 100.0, 10), ("BNTX", 50.0, 5), ("MRNA", 101.5, 20), ("MRNA", 99.0, 30)] {
+            feed.add_quote(Quote {
+                symbol: symbol.to_string(),
+                bid: last - 0.5,
+                ask: last + 0.5,
+                last,
+                volume,
+                timestamp: 0,
+            });
+        }
+
+        let closes: Vec<f64> = feed.closes("MRNA").collect();
+        assert_eq!(closes, vec![100.0, 101.5, 99.0]);
+        assert_eq!(feed.volumes("MRNA").sum::<u64>(), 60);
+        assert_eq!(feed.volumes("BNTX").collect::<Vec<_>>(), vec![5]);
+        assert_eq!(feed.closes("NONE").count(), 0);
+    }
+
+    #[test]
+    fn test_market_data_feed_book_and_spread_stats() {
+        let mut feed = MarketDataFeed::new();
+        for (timestamp, (bid, ask)) in [(100.0, 100.5), (100.0, 101.0), (101.0, 102.5)].into_iter().enumerate() {
+            feed.add_quote(Quote {
+                symbol: "TEST".to_string(),
+                bid,
+                ask,
+                last: bid,
+                volume: 100,
+                timestamp: timestamp as u64,
+            });
+        }
+
+        assert_eq!(feed.best_bid("TEST"), Some(101.0));
+        assert_eq!(feed.best_ask("TEST"), Some(102.5));
+        assert_eq!(feed.spread_stats("TEST"), Some((0.5, 1.0, 1.5)));
+
+        assert_eq!(feed.best_bid("NONE"), None);
+        assert_eq!(feed.spread_stats("NONE"), None);
+    }
+
+    #[test]
+    fn test_ohlcv_new_valid() {
+        let bar = OHLCV::new(100.0, 105.0, 98.0, 103.0, 1000, 1).unwrap();
+        assert!(bar.is_valid());
+        assert_eq!(bar.close, 103.0);
+    }
+
+    #[test]
+    fn test_ohlcv_new_high_below_low() {
+        let result = OHLCV::new(100.0, 95.0, 98.0, 97.0, 1000, 1);
+        assert!(result.unwrap_err().contains("high"));
+    }
+
+    #[test]
+    fn test_ohlcv_new_close_above_high() {
+        let result = OHLCV::new(100.0, 105.0, 98.0, 106.0, 1000, 1);
+        assert!(result.unwrap_err().contains("close"));
+    }
+
+    #[test]
+    fn test_ohlcv_is_valid_on_literal() {
+        let bar = OHLCV {
+            open: 110.0,
+            high: 105.0,
+            low: 98.0,
+            close: 100.0,
+            volume: 0,
+            timestamp: 0,
+        };
+        assert!(!bar.is_valid());
+    }
+
+    fn minute_bar(open: f64, high: f64, low: f64, close: f64, minute: u64) -> OHLCV {
+        OHLCV::new(open, high, low, close, 100 * (minute + 1), minute * 60).unwrap()
+    }
+
+    #[test]
+    fn test_resample_six_bars_by_three() {
+        let bars = vec![
+            minute_bar(10.0, 11.0, 9.5, 10.5, 0),
+            minute_bar(10.5, 12.0, 10.0, 11.5, 1),
+            minute_bar(11.5, 11.8, 10.8, 11.0, 2),
+            minute_bar(11.0, 11.2, 9.0, 9.5, 3),
+            minute_bar(9.5, 10.0, 9.2, 9.8, 4),
+            minute_bar(9.8, 10.6, 9.7, 10.4, 5),
+        ];
+        let coarse = resample(&bars, 3);
+        assert_eq!(coarse.len(), 2);
+
+        assert_eq!(coarse[0].open, 10.0);
+        assert_eq!(coarse[0].high, 12.0);
+        assert_eq!(coarse[0].low, 9.5);
+        assert_eq!(coarse[0].close, 11.0);
+        assert_eq!(coarse[0].volume, 600);
+        assert_eq!(coarse[0].timestamp, 0);
+
+        assert_eq!(coarse[1].open, 11.0);
+        assert_eq!(coarse[1].high, 11.2);
+        assert_eq!(coarse[1].low, 9.0);
+        assert_eq!(coarse[1].close, 10.4);
+        assert_eq!(coarse[1].volume, 1500);
+        assert_eq!(coarse[1].timestamp, 180);
+    }
+
+    #[test]
+    fn test_resample_partial_group_and_zero_factor() {
+        let bars: Vec<OHLCV> = (0..5).map(|m| minute_bar(10.0, 11.0, 9.0, 10.0, m)).collect();
+        let coarse = resample(&bars, 3);
+        assert_eq!(coarse.len(), 2);
+        assert_eq!(coarse[1].volume, 400 + 500);
+        assert!(resample(&bars, 0).is_empty());
+    }
+
+    #[test]
+    fn test_subscribe_fires_per_matching_quote() {
+        use std::sync::{Arc, Mutex};
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let everything = Arc::new(Mutex::new(0));
+        let mut feed = MarketDataFeed::new();
+        let sink = Arc::clone(&received);
+        feed.subscribe("MRNA", Box::new(move |q: &Quote| sink.lock().unwrap().push(q.last)));
+        let counter = Arc::clone(&everything);
+        feed.subscribe(ALL_SYMBOLS, Box::new(move |_: &Quote| *counter.lock().unwrap() += 1));
+
+        for (symbol, last) in [("MRNA", 100.0), ("BNTX", 50.0), ("MRNA", 101.0)] {
+            feed.add_quote(Quote {
+                symbol: symbol.to_string(),
+                bid: last - 0.1,
+                ask: last + 0.1,
+                last,
+                volume: 100,
+                timestamp: 0,
+            });
+        }
+
+        assert_eq!(*received.lock().unwrap(), vec![100.0, 101.0]);
+        assert_eq!(*everything.lock().unwrap(), 3);
+        assert_eq!(feed.get_all_quotes().len(), 3);
+    }
+
+    #[test]
+    fn test_feed_stores_bars_per_symbol() {
+        let mut feed = MarketDataFeed::new();
+        feed.add_bar("MRNA", OHLCV::new(10.0, 11.0, 9.0, 10.5, 100, 1).unwrap());
+        feed.add_bar("BNTX", OHLCV::new(20.0, 21.0, 19.0, 20.5, 100, 1).unwrap());
+        feed.add_bar("MRNA", OHLCV::new(10.5, 12.0, 10.0, 11.5, 100, 2).unwrap());
+
+        assert_eq!(feed.bars("MRNA").len(), 2);
+        assert_eq!(feed.bars("MRNA")[1].close, 11.5);
+        assert!(feed.bars("GILD").is_empty());
+        assert_eq!(feed.bar_symbols(), vec!["BNTX", "MRNA"]);
+    }
+
+    #[test]
+    fn test_quote_staleness() {
+        use crate::clock::MockClock;
+
+        let mut clock = MockClock::new(60_000);
+        let mut feed = MarketDataFeed::new();
+        feed.add_quote(Quote {
+            symbol: "MRNA".to_string(),
+            bid: 99.9,
+            ask: 100.1,
+            last: 100.0,
+            volume: 100,
+            timestamp: 60_000,
+        });
+
+        assert!(feed.fresh_quote("MRNA", &clock, 1_000).is_some());
+        clock.advance(1_001);
+        assert!(feed.latest_quote("MRNA").unwrap().is_stale(&clock, 1_000));
+        assert!(feed.fresh_quote("MRNA", &clock, 1_000).is_none());
+    }
 }
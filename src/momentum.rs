@@ -1,6 +1,10 @@
 // Momentum Indicators
 // Technical analysis for trading signals
 
+use serde::{Deserialize, Serialize};
+
+use crate::market_data::OHLCV;
+
 pub struct MomentumIndicator {
     pub name: String,
     pub value: f64,
@@ -43,6 +47,145 @@ pub fn calculate_rsi(prices: &[f64], period: usize) -> f64 {
     100.0 - (100.0 / (1.0 + rs))
 }
 
+// RSI for every window of `period + 1` prices; entry i lines up with prices[i + period]
+pub fn rsi_series(prices: &[f64], period: usize) -> Vec<f64> {
+    if period == 0 {
+        return Vec::new();
+    }
+    windows_rolling(prices, period + 1)
+        .map(|window| calculate_rsi(window, period))
+        .collect()
+}
+
+// Online RSI: seeds with a simple average of the first `period` changes (matching
+// calculate_rsi), then applies Wilder smoothing in O(1) per price. Only the seed
+// value agrees with rsi_series; later values carry history that the simple
+// windowed average drops, so the two diverge after `period + 1` prices.
+pub struct IncrementalRsi {
+    period: usize,
+    prev_price: Option<f64>,
+    changes_seen: usize,
+    avg_gain: f64,
+    avg_loss: f64,
+}
+
+impl IncrementalRsi {
+    pub fn new(period: usize) -> Self {
+        IncrementalRsi {
+            period: period.max(1),
+            prev_price: None,
+            changes_seen: 0,
+            avg_gain: 0.0,
+            avg_loss: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, price: f64) -> Option<f64> {
+        let prev = self.prev_price.replace(price)?;
+        let change = price - prev;
+        let (gain, loss) = (change.max(0.0), (-change).max(0.0));
+        let period = self.period as f64;
+
+        self.changes_seen += 1;
+        if self.changes_seen <= self.period {
+            // Still accumulating the seed window
+            self.avg_gain += gain / period;
+            self.avg_loss += loss / period;
+            if self.changes_seen < self.period {
+                return None;
+            }
+        } else {
+            self.avg_gain = (self.avg_gain * (period - 1.0) + gain) / period;
+            self.avg_loss = (self.avg_loss * (period - 1.0) + loss) / period;
+        }
+        Some(self.value())
+    }
+
+    fn value(&self) -> f64 {
+        if self.avg_loss == 0.0 {
+            return 100.0;
+        }
+        let rs = self.avg_gain / self.avg_loss;
+        100.0 - (100.0 / (1.0 + rs))
+    }
+}
+
+// Online SMA over a ring buffer; the running sum makes each update O(1)
+pub struct IncrementalSma {
+    window: Vec<f64>,
+    next: usize,
+    filled: bool,
+    sum: f64,
+}
+
+impl IncrementalSma {
+    pub fn new(period: usize) -> Self {
+        IncrementalSma {
+            window: vec![0.0; period.max(1)],
+            next: 0,
+            filled: false,
+            sum: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, price: f64) -> Option<f64> {
+        self.sum += price - self.window[self.next];
+        self.window[self.next] = price;
+        self.next = (self.next + 1) % self.window.len();
+        if self.next == 0 {
+            self.filled = true;
+        }
+        self.filled.then(|| self.sum / self.window.len() as f64)
+    }
+}
+
+// Online EMA: the first `period` prices seed it with their simple average, then the
+// usual recurrence applies. Matches ema_series
+pub struct IncrementalEma {
+    period: usize,
+    alpha: f64,
+    seen: usize,
+    value: f64,
+}
+
+impl IncrementalEma {
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        IncrementalEma {
+            period,
+            alpha: 2.0 / (period as f64 + 1.0),
+            seen: 0,
+            value: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, price: f64) -> Option<f64> {
+        self.seen += 1;
+        if self.seen <= self.period {
+            self.value += price / self.period as f64;
+            return (self.seen == self.period).then_some(self.value);
+        }
+        self.value = self.alpha * price + (1.0 - self.alpha) * self.value;
+        Some(self.value)
+    }
+}
+
+// EMA seeded with the SMA of the first `period` prices; entry i lines up with
+// prices[i + period - 1]
+pub fn ema_series(prices: &[f64], period: usize) -> Vec<f64> {
+    if period == 0 || prices.len() < period {
+        return Vec::new();
+    }
+    let alpha = 2.0 / (period as f64 + 1.0);
+    let mut ema = prices[..period].iter().sum::<f64>() / period as f64;
+    let mut series = vec![ema];
+    for &price in &prices[period..] {
+        ema = alpha * price + (1.0 - alpha) * ema;
+        series.push(ema);
+    }
+    series
+}
+
 pub fn calculate_macd(prices: &[f64]) -> (f64, f64, f64) {
     let ema12 = calculate_ema(prices, 12);
     let ema26 = calculate_ema(prices, 26);
@@ -52,6 +195,164 @@ pub fn calculate_macd(prices: &[f64]) -> (f64, f64, f64) {
     (macd_line, signal_line, histogram)
 }
 
+// Slide a window of `size` over data; yields nothing if size is 0 or exceeds the length
+pub fn windows_rolling<T>(data: &[T], size: usize) -> impl Iterator<Item = &[T]> {
+    // windows(len + 1) is always empty, which also covers size == 0
+    let size = if size == 0 { data.len() + 1 } else { size };
+    data.windows(size)
+}
+
+pub fn calculate_sma(prices: &[f64], period: usize) -> Vec<f64> {
+    windows_rolling(prices, period)
+        .map(|window| window.iter().sum::<f64>() / window.len() as f64)
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BollingerBand {
+    pub upper: f64,
+    pub middle: f64,
+    pub lower: f64,
+}
+
+pub fn calculate_bollinger_bands(prices: &[f64], period: usize, num_std: f64) -> Vec<BollingerBand> {
+    windows_rolling(prices, period)
+        .map(|window| {
+            let n = window.len() as f64;
+            let middle = window.iter().sum::<f64>() / n;
+            let variance = window.iter().map(|p| (p - middle).powi(2)).sum::<f64>() / n;
+            let band = num_std * variance.sqrt();
+            BollingerBand {
+                upper: middle + band,
+                middle,
+                lower: middle - band,
+            }
+        })
+        .collect()
+}
+
+// Williams %R over the last `period` bars: 0 at the high of the range, -100 at the low
+pub fn williams_r(bars: &[OHLCV], period: usize) -> Option<f64> {
+    if period == 0 || bars.len() < period {
+        return None;
+    }
+    let window = &bars[bars.len() - period..];
+    let highest_high = window.iter().map(|b| b.high).fold(f64::MIN, f64::max);
+    let lowest_low = window.iter().map(|b| b.low).fold(f64::MAX, f64::min);
+    let range = highest_high - lowest_low;
+    if range == 0.0 {
+        return None;
+    }
+    let close = window[window.len() - 1].close;
+    Some(-100.0 * (highest_high - close) / range)
+}
+
+// Rate of change, in percent, between the last price and the one `period` bars earlier
+pub fn roc(prices: &[f64], period: usize) -> Option<f64> {
+    if period == 0 || prices.len() <= period {
+        return None;
+    }
+    let current = prices[prices.len() - 1];
+    let previous = prices[prices.len() - 1 - period];
+    if previous == 0.0 {
+        return None;
+    }
+    Some(100.0 * (current - previous) / previous)
+}
+
+// Fraction of series values at or below `value`, in [0, 1]; 0 for an empty series
+pub fn percentile_rank(series: &[f64], value: f64) -> f64 {
+    if series.is_empty() {
+        return 0.0;
+    }
+    series.iter().filter(|&&v| v <= value).count() as f64 / series.len() as f64
+}
+
+// Percentile rank of the latest one-bar ROC among the last `lookback` one-bar ROCs
+// (itself included), so 1.0 is the strongest move in the window. Needs lookback + 1 prices
+pub fn normalized_momentum(prices: &[f64], lookback: usize) -> Option<f64> {
+    if lookback == 0 || prices.len() <= lookback {
+        return None;
+    }
+    let recent = &prices[prices.len() - 1 - lookback..];
+    let history = recent
+        .windows(2)
+        .map(|pair| roc(pair, 1))
+        .collect::<Option<Vec<f64>>>()?;
+    Some(percentile_rank(&history, history[history.len() - 1]))
+}
+
+// Average true range: the simple mean of the last `period` true ranges, each of
+// which also spans the gap from the previous close. Needs period + 1 bars
+pub fn atr(bars: &[OHLCV], period: usize) -> Option<f64> {
+    if period == 0 || bars.len() <= period {
+        return None;
+    }
+    let window = &bars[bars.len() - period - 1..];
+    let total: f64 = window
+        .windows(2)
+        .map(|w| {
+            let prev_close = w[0].close;
+            let bar = &w[1];
+            (bar.high - bar.low)
+                .max((bar.high - prev_close).abs())
+                .max((bar.low - prev_close).abs())
+        })
+        .sum();
+    Some(total / period as f64)
+}
+
+// Lookback periods for compute_all. MACD always uses the standard 12/26 EMAs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndicatorConfig {
+    pub rsi_period: usize,
+    pub sma_period: usize,
+    pub bollinger_period: usize,
+    pub bollinger_std: f64,
+    pub atr_period: usize,
+}
+
+impl Default for IndicatorConfig {
+    fn default() -> Self {
+        IndicatorConfig {
+            rsi_period: 14,
+            sma_period: 20,
+            bollinger_period: 20,
+            bollinger_std: 2.0,
+            atr_period: 14,
+        }
+    }
+}
+
+// Every indicator as of the latest bar; None where there isn't enough history
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndicatorSnapshot {
+    pub rsi: Option<f64>,
+    pub macd: Option<(f64, f64, f64)>,
+    pub sma: Option<f64>,
+    pub bollinger: Option<BollingerBand>,
+    pub atr: Option<f64>,
+}
+
+pub fn compute_all(bars: &[OHLCV], cfg: &IndicatorConfig) -> IndicatorSnapshot {
+    let closes: Vec<f64> = bars.iter().map(|b| b.close).collect();
+    let tail = |len: usize| &closes[closes.len() - len..];
+
+    let rsi = (cfg.rsi_period > 0 && closes.len() > cfg.rsi_period)
+        .then(|| calculate_rsi(tail(cfg.rsi_period + 1), cfg.rsi_period));
+    let macd = (closes.len() >= 26).then(|| calculate_macd(&closes));
+    let sma = calculate_sma(&closes, cfg.sma_period).last().copied();
+    let bollinger = calculate_bollinger_bands(&closes, cfg.bollinger_period, cfg.bollinger_std).pop();
+
+    IndicatorSnapshot {
+        rsi,
+        macd,
+        sma,
+        bollinger,
+        atr: atr(bars, cfg.atr_period),
+    }
+}
+
 fn calculate_ema(prices: &[f64], period: usize) -> f64 {
     if prices.is_empty() {
         return 0.0;
@@ -96,6 +397,53 @@ mod tests {
         assert!(rsi > 0.0 && rsi < 100.0);
     }
 
+    #[test]
+    fn test_rsi_series() {
+        let prices = vec![1.0, 2.0, 3.0, 2.0, 1.0];
+        let series = rsi_series(&prices, 2);
+        assert_eq!(series, vec![100.0, 50.0, 0.0]);
+        assert_eq!(series[0], calculate_rsi(&prices[..3], 2));
+        assert!(rsi_series(&prices, 5).is_empty());
+        assert!(rsi_series(&prices, 0).is_empty());
+    }
+
+    #[test]
+    fn test_incremental_rsi_matches_batch() {
+        let prices = vec![100.0, 101.0, 100.5, 101.5, 100.8, 102.0, 101.0, 102.5, 101.5, 103.0, 102.0, 103.5, 102.5, 104.0, 103.0];
+        let mut rsi = IncrementalRsi::new(14);
+        let outputs: Vec<Option<f64>> = prices.iter().map(|&p| rsi.update(p)).collect();
+
+        assert!(outputs[..14].iter().all(Option::is_none));
+        let last = outputs[14].unwrap();
+        assert!((last - calculate_rsi(&prices, 14)).abs() < 1e-9);
+
+        // Past the seed, Wilder smoothing and the windowed simple average disagree
+        let longer: Vec<f64> = prices.iter().copied().chain([98.0, 97.0, 99.0, 96.0]).collect();
+        let batch = rsi_series(&longer, 14);
+        let mut rsi = IncrementalRsi::new(14);
+        let online: Vec<f64> = longer.iter().filter_map(|&p| rsi.update(p)).collect();
+        assert_eq!(online.len(), batch.len());
+        assert!((online[0] - batch[0]).abs() < 1e-9);
+        assert!(batch.iter().zip(&online).skip(1).all(|(b, o)| (b - o).abs() > 1e-6));
+    }
+
+    #[test]
+    fn test_incremental_rsi_wilder_smoothing() {
+        let mut rsi = IncrementalRsi::new(2);
+        assert_eq!(rsi.update(10.0), None);
+        assert_eq!(rsi.update(12.0), None);
+        // Seed: gains 2, losses 1 over 2 changes -> avg 1.0 / 0.5
+        let seeded = rsi.update(11.0).unwrap();
+        assert!((seeded - 100.0 * 2.0 / 3.0).abs() < 1e-9);
+        // Wilder: gain (1.0 + 3) / 2 = 2.0, loss (0.5 + 0) / 2 = 0.25 -> RS 8
+        let next = rsi.update(14.0).unwrap();
+        assert!((next - (100.0 - 100.0 / 9.0)).abs() < 1e-9);
+
+        let mut rising = IncrementalRsi::new(3);
+        let last = (0..10).map(|i| rising.update(i as f64)).last().flatten();
+        assert_eq!(last, Some(100.0));
+    }
+
     #[test]
     fn test_calculate_macd() {
         let prices = vec![100.0, 101.0, 102.0, 103.0, 104.0, 105.0, 106.0, 107.0, 108.0, 109.0, 110.0, 111.0, 112.0];
@@ -125,4 +473,184 @@ mod tests {
         let ema = calculate_ema(&prices, 3);
         assert!(ema > 100.0 && ema <= 106.0);
     }
+
+    #[test]
+    fn test_windows_rolling_sliding() {
+        let data = [1, 2, 3, 4];
+        let windows: Vec<&[i32]> = windows_rolling(&data, 2).collect();
+        assert_eq!(windows, vec![&[1, 2][..], &[2, 3][..], &[3, 4][..]]);
+    }
+
+    #[test]
+    fn test_windows_rolling_size_equal_to_len() {
+        let data = [1, 2, 3];
+        let windows: Vec<&[i32]> = windows_rolling(&data, 3).collect();
+        assert_eq!(windows, vec![&[1, 2, 3][..]]);
+    }
+
+    #[test]
+    fn test_windows_rolling_size_too_large_or_zero() {
+        let data = [1, 2, 3];
+        assert_eq!(windows_rolling(&data, 4).count(), 0);
+        assert_eq!(windows_rolling(&data, 0).count(), 0);
+        assert_eq!(windows_rolling::<i32>(&[], 1).count(), 0);
+    }
+
+    #[test]
+    fn test_calculate_sma() {
+        let prices = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        assert_eq!(calculate_sma(&prices, 3), vec![2.0, 3.0, 4.0]);
+        assert!(calculate_sma(&prices, 6).is_empty());
+    }
+
+    #[test]
+    fn test_calculate_bollinger_bands() {
+        let prices = vec![2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        let bands = calculate_bollinger_bands(&prices, 8, 2.0);
+        assert_eq!(bands.len(), 1);
+        // Mean 5, population std dev 2
+        assert_eq!(bands[0].middle, 5.0);
+        assert_eq!(bands[0].upper, 9.0);
+        assert_eq!(bands[0].lower, 1.0);
+
+        let flat = calculate_bollinger_bands(&[3.0; 5], 3, 2.0);
+        assert_eq!(flat.len(), 3);
+        assert!(flat.iter().all(|b| b.upper == b.lower));
+    }
+
+    fn bar(high: f64, low: f64, close: f64) -> OHLCV {
+        OHLCV::new(low, high, low, close, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_williams_r() {
+        let bars = vec![bar(10.0, 8.0, 9.0), bar(12.0, 9.0, 11.0), bar(11.0, 7.0, 10.0)];
+        // Range 7..12, close 10 -> -100 * 2 / 5
+        assert_eq!(williams_r(&bars, 3), Some(-40.0));
+        // Last two bars only: range 7..12 still, close 10
+        assert_eq!(williams_r(&bars, 2), Some(-40.0));
+        assert_eq!(williams_r(&bars[..1], 1), Some(-50.0));
+        assert_eq!(williams_r(&bars, 4), None);
+        assert_eq!(williams_r(&bars, 0), None);
+    }
+
+    #[test]
+    fn test_williams_r_flat_range() {
+        let bars = vec![bar(5.0, 5.0, 5.0); 3];
+        assert_eq!(williams_r(&bars, 3), None);
+    }
+
+    #[test]
+    fn test_roc() {
+        let prices = vec![100.0, 105.0, 110.0, 121.0];
+        assert_eq!(roc(&prices, 3), Some(21.0));
+        assert_eq!(roc(&prices, 1), Some(10.0));
+        assert_eq!(roc(&prices, 4), None);
+        assert_eq!(roc(&prices, 0), None);
+        assert_eq!(roc(&[0.0, 5.0], 1), None);
+    }
+
+    #[test]
+    fn test_percentile_rank_extremes() {
+        let series = vec![3.0, 1.0, 4.0, 1.5, 9.0, 2.6];
+        assert_eq!(percentile_rank(&series, 9.0), 1.0);
+        // The minimum still counts itself
+        assert!((percentile_rank(&series, 1.0) - 1.0 / 6.0).abs() < 1e-12);
+        assert_eq!(percentile_rank(&series, 0.5), 0.0);
+        assert_eq!(percentile_rank(&series, 2.6), 0.5);
+        assert_eq!(percentile_rank(&[], 1.0), 0.0);
+    }
+
+    #[test]
+    fn test_normalized_momentum() {
+        // Latest move (+10%) is the biggest of the last four
+        let surging = vec![100.0, 101.0, 103.0, 102.0, 112.2];
+        assert_eq!(normalized_momentum(&surging, 4), Some(1.0));
+        // Latest move is the worst of the four, so only it ranks at or below itself
+        let slumping = vec![100.0, 101.0, 103.0, 104.0, 90.0];
+        assert_eq!(normalized_momentum(&slumping, 4), Some(0.25));
+        assert_eq!(normalized_momentum(&surging, 5), None);
+        assert_eq!(normalized_momentum(&surging, 0), None);
+    }
+
+    #[test]
+    fn test_atr() {
+        // True ranges: 3 (12 - 9), then 4 (gap down from close 11 to low 7)
+        let bars = vec![bar(10.0, 8.0, 9.0), bar(12.0, 9.0, 11.0), bar(10.0, 7.0, 8.0)];
+        assert_eq!(atr(&bars, 2), Some(3.5));
+        assert_eq!(atr(&bars, 1), Some(4.0));
+        assert_eq!(atr(&bars, 3), None);
+        assert_eq!(atr(&bars, 0), None);
+    }
+
+    #[test]
+    fn test_compute_all_thirty_bars() {
+        let bars: Vec<OHLCV> = (0..30)
+            .map(|i| {
+                // Zig-zag uptrend: +4 then -2, so RSI settles at 100 - 100 / 3
+                let close = 100.0 + i as f64 + if i % 2 == 0 { 1.5 } else { -1.5 };
+                bar(close + 1.0, close - 1.0, close)
+            })
+            .collect();
+        let snapshot = compute_all(&bars, &IndicatorConfig::default());
+
+        let rsi = snapshot.rsi.unwrap();
+        assert!((rsi - 200.0 / 3.0).abs() < 1e-9);
+        let (macd_line, _, _) = snapshot.macd.unwrap();
+        assert!(macd_line > 0.0);
+        let sma = snapshot.sma.unwrap();
+        assert!(sma > 100.0 && sma < bars[29].close);
+        let band = snapshot.bollinger.unwrap();
+        assert!(band.lower < band.middle && band.middle < band.upper);
+        assert_eq!(band.middle, sma);
+        let atr = snapshot.atr.unwrap();
+        // Gaps make the true ranges 5 after an up move and 3 after a down move
+        assert!((atr - 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_compute_all_insufficient_history() {
+        let bars = vec![bar(10.0, 8.0, 9.0); 5];
+        let snapshot = compute_all(&bars, &IndicatorConfig::default());
+        assert_eq!(snapshot.rsi, None);
+        assert_eq!(snapshot.macd, None);
+        assert_eq!(snapshot.sma, None);
+        assert_eq!(snapshot.bollinger, None);
+        assert_eq!(snapshot.atr, None);
+    }
+
+    #[test]
+    fn test_incremental_sma_matches_batch() {
+        let prices: Vec<f64> = (0..30).map(|i| 100.0 + (i as f64 * 0.7).sin() * 5.0).collect();
+        let mut sma = IncrementalSma::new(5);
+        let online: Vec<f64> = prices.iter().filter_map(|&p| sma.update(p)).collect();
+        let batch = calculate_sma(&prices, 5);
+        assert_eq!(online.len(), batch.len());
+        assert!(online.iter().zip(&batch).all(|(a, b)| (a - b).abs() < 1e-9));
+
+        let mut short = IncrementalSma::new(3);
+        assert_eq!(short.update(1.0), None);
+        assert_eq!(short.update(2.0), None);
+        assert_eq!(short.update(3.0), Some(2.0));
+        assert_eq!(short.update(10.0), Some(5.0));
+    }
+
+    #[test]
+    fn test_incremental_ema_matches_batch() {
+        let prices: Vec<f64> = (0..40).map(|i| 50.0 + i as f64 * 0.3 + (i as f64).cos()).collect();
+        let mut ema = IncrementalEma::new(10);
+        let online: Vec<f64> = prices.iter().filter_map(|&p| ema.update(p)).collect();
+        let batch = ema_series(&prices, 10);
+        assert_eq!(online.len(), 31);
+        assert_eq!(online.len(), batch.len());
+        assert!((online.last().unwrap() - batch.last().unwrap()).abs() < 1e-9);
+
+        let mut seeded = IncrementalEma::new(3);
+        assert_eq!(seeded.update(3.0), None);
+        assert_eq!(seeded.update(6.0), None);
+        // Seed is the SMA of the first three prices
+        assert_eq!(seeded.update(9.0), Some(6.0));
+        assert_eq!(seeded.update(10.0), Some(8.0));
+        assert!(ema_series(&[1.0, 2.0], 3).is_empty());
+    }
 }
@@ -0,0 +1,73 @@
+// Money Formatting
+// Currency symbol, precision and digit grouping for every displayed amount
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoneyFormat {
+    pub symbol: String,
+    pub decimals: usize,
+    pub thousands_sep: bool,
+}
+
+// Plain "$1234.56", matching what the displays always printed
+impl Default for MoneyFormat {
+    fn default() -> Self {
+        Self::new("$", 2, false)
+    }
+}
+
+impl MoneyFormat {
+    pub fn new(symbol: &str, decimals: usize, thousands_sep: bool) -> Self {
+        MoneyFormat {
+            symbol: symbol.to_string(),
+            decimals,
+            thousands_sep,
+        }
+    }
+}
+
+// Sign first, then the symbol: -$1,234.50
+pub fn format_money(amount: f64, fmt: &MoneyFormat) -> String {
+    let digits = format!("{:.*}", fmt.decimals, amount.abs());
+    let (whole, fraction) = match digits.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (digits.as_str(), None),
+    };
+
+    let mut grouped = String::with_capacity(whole.len() + whole.len() / 3);
+    for (i, c) in whole.chars().enumerate() {
+        if fmt.thousands_sep && i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    if let Some(fraction) = fraction {
+        grouped.push('.');
+        grouped.push_str(fraction);
+    }
+
+    // Rounding can leave "-0.00"; don't show a sign on zero
+    let sign = if amount < 0.0 && digits.chars().any(|c| c.is_ascii_digit() && c != '0') { "-" } else { "" };
+    format!("{}{}{}", sign, fmt.symbol, grouped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_money_usd_with_separators() {
+        let usd = MoneyFormat::new("$", 2, true);
+        assert_eq!(format_money(1_000_000.0, &usd), "$1,000,000.00");
+        assert_eq!(format_money(999.5, &usd), "$999.50");
+        assert_eq!(format_money(-1_234.5, &usd), "-$1,234.50");
+        assert_eq!(format_money(-0.001, &usd), "$0.00");
+        assert_eq!(format_money(1_000_000.0, &MoneyFormat::default()), "$1000000.00");
+    }
+
+    #[test]
+    fn test_format_money_euro_zero_decimals() {
+        let eur = MoneyFormat::new("€", 0, true);
+        assert_eq!(format_money(1_234_567.6, &eur), "€1,234,568");
+        assert_eq!(format_money(12.0, &eur), "€12");
+    }
+}
@@ -1,8 +1,18 @@
 // P&L (Profit and Loss) Calculation
 // Portfolio performance tracking
 
+use std::fs;
+
+use crate::money::{format_money, MoneyFormat};
+use crate::snapshot::{Resettable, Snapshot};
+use crate::trading::Trade;
 use crate::trading_models::Position;
 
+// Column names matching PnLReport::to_csv_row
+pub const PNL_CSV_HEADER: &str = "realized_pnl,unrealized_pnl,total_pnl,return_pct";
+
+pub const TRADES_CSV_HEADER: &str = "id,symbol,side,quantity,price,timestamp";
+
 #[derive(Debug, Clone)]
 pub struct PnLReport {
     pub realized_pnl: f64,
@@ -13,13 +23,66 @@ pub struct PnLReport {
 
 impl PnLReport {
     pub fn display(&self) -> String {
+        self.display_with(&MoneyFormat::default())
+    }
+
+    pub fn display_with(&self, fmt: &MoneyFormat) -> String {
         format!(
-            "P&L Report:\n  Realized: ${:.2}\n  Unrealized: ${:.2}\n  Total: ${:.2}\n  Return: {:.2}%",
+            "P&L Report:\n  Realized: {}\n  Unrealized: {}\n  Total: {}\n  Return: {:.2}%",
+            format_money(self.realized_pnl, fmt),
+            format_money(self.unrealized_pnl, fmt),
+            format_money(self.total_pnl, fmt),
+            self.return_pct
+        )
+    }
+
+    pub fn to_csv_row(&self) -> String {
+        format!(
+            "{:.2},{:.2},{:.2},{:.2}",
             self.realized_pnl, self.unrealized_pnl, self.total_pnl, self.return_pct
         )
     }
 }
 
+// Realized P&L is the only accumulated state; initial capital is configuration
+impl Resettable for PnLCalculator {
+    fn reset(&mut self) {
+        self.realized_pnl = 0.0;
+    }
+}
+
+impl Snapshot for PnLCalculator {
+    type Snap = f64;
+
+    fn snapshot(&self) -> Self::Snap {
+        self.realized_pnl
+    }
+
+    fn restore(&mut self, snap: Self::Snap) {
+        self.realized_pnl = snap;
+    }
+}
+
+// One row per (symbol, report) under a symbol-prefixed header
+pub fn write_reports_csv(path: &str, reports: &[(String, PnLReport)]) -> Result<(), String> {
+    let mut csv = format!("symbol,{}\n", PNL_CSV_HEADER);
+    for (symbol, report) in reports {
+        csv.push_str(&format!("{},{}\n", symbol, report.to_csv_row()));
+    }
+    fs::write(path, csv).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+pub fn write_trades_csv(path: &str, trades: &[Trade]) -> Result<(), String> {
+    let mut csv = format!("{}\n", TRADES_CSV_HEADER);
+    for trade in trades {
+        csv.push_str(&format!(
+            "{},{},{:?},{:.2},{:.2},{}\n",
+            trade.id, trade.symbol, trade.side, trade.quantity, trade.price, trade.timestamp
+        ));
+    }
+    fs::write(path, csv).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
 pub struct PnLCalculator {
     initial_capital: f64,
     realized_pnl: f64,
@@ -33,6 +96,15 @@ impl PnLCalculator {
         }
     }
 
+    pub fn initial_capital(&self) -> f64 {
+        self.initial_capital
+    }
+
+    // Realized P&L is kept; only the base that returns are measured against moves
+    pub fn set_initial_capital(&mut self, amount: f64) {
+        self.initial_capital = amount;
+    }
+
     pub fn add_realized_pnl(&mut self, pnl: f64) {
         self.realized_pnl += pnl;
     }
@@ -51,6 +123,179 @@ impl PnLCalculator {
     }
 }
 
+// Several positions managed as one unit, e.g. a long/short pair
+#[derive(Debug, Clone)]
+pub struct Strategy {
+    pub name: String,
+    pub legs: Vec<Position>,
+}
+
+impl Strategy {
+    pub fn new(name: &str, legs: Vec<Position>) -> Self {
+        Strategy {
+            name: name.to_string(),
+            legs,
+        }
+    }
+
+    pub fn strategy_pnl(&self) -> f64 {
+        self.legs.iter().map(|leg| leg.unrealized_pnl()).sum()
+    }
+
+    // Signed quantity summed across legs; 0 for a share-neutral pair
+    pub fn net_delta(&self) -> f64 {
+        self.legs.iter().map(|leg| leg.quantity).sum()
+    }
+}
+
+// Excess mean return over downside deviation (only returns below risk_free count)
+pub fn sortino_ratio(returns: &[f64], risk_free: f64) -> f64 {
+    if returns.is_empty() {
+        return 0.0;
+    }
+    let n = returns.len() as f64;
+    let excess_mean = returns.iter().map(|r| r - risk_free).sum::<f64>() / n;
+    let downside_var = returns
+        .iter()
+        .map(|r| (r - risk_free).min(0.0).powi(2))
+        .sum::<f64>()
+        / n;
+
+    if downside_var == 0.0 {
+        return f64::INFINITY;
+    }
+    excess_mean / downside_var.sqrt()
+}
+
+// Compound annual growth rate between two equity values
+pub fn cagr(start_equity: f64, end_equity: f64, years: f64) -> f64 {
+    if start_equity <= 0.0 || end_equity <= 0.0 || years <= 0.0 {
+        return 0.0;
+    }
+    (end_equity / start_equity).powf(1.0 / years) - 1.0
+}
+
+// Historical value at risk: the loss at the (1 - confidence) quantile of past returns,
+// as a positive fraction (0 if even that tail return is a gain)
+pub fn historical_var(returns: &[f64], confidence: f64) -> Option<f64> {
+    if returns.is_empty() {
+        return None;
+    }
+    let confidence = confidence.clamp(f64::EPSILON, 1.0 - f64::EPSILON);
+    let mut sorted = returns.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+
+    let index = (((1.0 - confidence) * sorted.len() as f64).floor() as usize).min(sorted.len() - 1);
+    Some((-sorted[index]).max(0.0))
+}
+
+// Annualized realized volatility: sample stddev of log returns scaled by
+// sqrt(periods_per_year). None with fewer than two returns or if any price is
+// non-positive, since the log return is undefined there
+pub fn historical_volatility(prices: &[f64], periods_per_year: f64) -> Option<f64> {
+    if prices.len() < 3 || prices.iter().any(|&p| p <= 0.0) {
+        return None;
+    }
+    let log_returns: Vec<f64> = prices.windows(2).map(|w| (w[1] / w[0]).ln()).collect();
+    let n = log_returns.len() as f64;
+    let mean = log_returns.iter().sum::<f64>() / n;
+    let variance = log_returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    Some(variance.sqrt() * periods_per_year.sqrt())
+}
+
+// Simple period-over-period returns; a zero price yields a 0 return rather than infinity
+fn simple_returns(prices: &[f64]) -> Vec<f64> {
+    prices
+        .windows(2)
+        .map(|w| if w[0] == 0.0 { 0.0 } else { w[1] / w[0] - 1.0 })
+        .collect()
+}
+
+fn pearson(a: &[f64], b: &[f64]) -> f64 {
+    let n = a.len() as f64;
+    if a.is_empty() {
+        return 0.0;
+    }
+    let mean_a = a.iter().sum::<f64>() / n;
+    let mean_b = b.iter().sum::<f64>() / n;
+    let cov: f64 = a.iter().zip(b).map(|(x, y)| (x - mean_a) * (y - mean_b)).sum();
+    let var_a: f64 = a.iter().map(|x| (x - mean_a).powi(2)).sum();
+    let var_b: f64 = b.iter().map(|y| (y - mean_b).powi(2)).sum();
+    if var_a == 0.0 || var_b == 0.0 {
+        return 0.0;
+    }
+    cov / (var_a.sqrt() * var_b.sqrt())
+}
+
+// Pairwise Pearson correlation of returns. Series are truncated to the shortest
+// length; a constant series has undefined correlation and is reported as 0.0
+// against everything else. The diagonal is always 1.0
+pub fn correlation_matrix(series: &[(String, Vec<f64>)]) -> Vec<Vec<f64>> {
+    let len = series.iter().map(|(_, prices)| prices.len()).min().unwrap_or(0);
+    let returns: Vec<Vec<f64>> = series.iter().map(|(_, prices)| simple_returns(&prices[..len])).collect();
+
+    let n = series.len();
+    let mut matrix = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        matrix[i][i] = 1.0;
+        for j in (i + 1)..n {
+            let rho = pearson(&returns[i], &returns[j]);
+            matrix[i][j] = rho;
+            matrix[j][i] = rho;
+        }
+    }
+    matrix
+}
+
+// Pairs trading: z-score of the latest spread a - hedge_ratio * b against the mean
+// and stddev of the `lookback` spreads before it. None without lookback + 1 aligned
+// points or if the spread never moved over the lookback
+pub fn spread_zscore(series_a: &[f64], series_b: &[f64], hedge_ratio: f64, lookback: usize) -> Option<f64> {
+    let len = series_a.len().min(series_b.len());
+    if lookback < 2 || len < lookback + 1 {
+        return None;
+    }
+    let spreads: Vec<f64> = series_a[len - lookback - 1..len]
+        .iter()
+        .zip(&series_b[len - lookback - 1..len])
+        .map(|(a, b)| a - hedge_ratio * b)
+        .collect();
+    let (history, latest) = spreads.split_at(lookback);
+
+    let mean = history.iter().sum::<f64>() / lookback as f64;
+    let std = (history.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / lookback as f64).sqrt();
+    if std == 0.0 {
+        return None;
+    }
+    Some((latest[0] - mean) / std)
+}
+
+// CAPM regression of strategy returns on a benchmark, returned as (alpha, beta).
+// None when the series differ in length, are empty, or the benchmark never moves
+pub fn alpha_beta(strategy_returns: &[f64], benchmark_returns: &[f64]) -> Option<(f64, f64)> {
+    if strategy_returns.is_empty() || strategy_returns.len() != benchmark_returns.len() {
+        return None;
+    }
+    let n = strategy_returns.len() as f64;
+    let mean_strategy = strategy_returns.iter().sum::<f64>() / n;
+    let mean_bench = benchmark_returns.iter().sum::<f64>() / n;
+
+    let covariance = strategy_returns
+        .iter()
+        .zip(benchmark_returns)
+        .map(|(s, b)| (s - mean_strategy) * (b - mean_bench))
+        .sum::<f64>()
+        / n;
+    let bench_var = benchmark_returns.iter().map(|b| (b - mean_bench).powi(2)).sum::<f64>() / n;
+
+    if bench_var == 0.0 {
+        return None;
+    }
+    let beta = covariance / bench_var;
+    let alpha = mean_strategy - beta * mean_bench;
+    Some((alpha, beta))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -79,6 +324,21 @@ mod tests {
         assert_eq!(report.total_pnl, 0.0);
     }
 
+    #[test]
+    fn test_pnl_calculator_initial_capital() {
+        let calc = PnLCalculator::new(25_000.0);
+        assert_eq!(calc.initial_capital(), 25_000.0);
+    }
+
+    #[test]
+    fn test_pnl_calculator_set_initial_capital_keeps_realized() {
+        let mut calc = PnLCalculator::new(25_000.0);
+        calc.add_realized_pnl(500.0);
+        calc.set_initial_capital(50_000.0);
+        assert_eq!(calc.initial_capital(), 50_000.0);
+        assert_eq!(calc.snapshot(), 500.0);
+    }
+
     #[test]
     fn test_pnl_calculator_add_realized_pnl() {
         let mut calc = PnLCalculator::new(10000.0);
@@ -121,4 +381,245 @@ mod tests {
         let report = calc.calculate_report(&[]);
         assert_eq!(report.return_pct, -5.0);
     }
+
+    #[test]
+    fn test_sortino_ratio_asymmetric_downside() {
+        let returns = vec![0.05, 0.03, -0.02, 0.04, -0.01];
+        // Excess mean 0.018; downside deviation sqrt((0.0004 + 0.0001) / 5) = 0.01
+        let ratio = sortino_ratio(&returns, 0.0);
+        assert!((ratio - 1.8).abs() < 1e-9);
+
+        // Larger upside moves don't reduce the ratio
+        let more_upside = vec![0.10, 0.03, -0.02, 0.04, -0.01];
+        assert!(sortino_ratio(&more_upside, 0.0) > ratio);
+    }
+
+    #[test]
+    fn test_sortino_ratio_no_downside() {
+        assert_eq!(sortino_ratio(&[0.01, 0.02, 0.03], 0.0), f64::INFINITY);
+        assert_eq!(sortino_ratio(&[], 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_cagr_doubling_over_two_years() {
+        let growth = cagr(100_000.0, 200_000.0, 2.0);
+        assert!((growth - 0.4142).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_cagr_invalid_inputs() {
+        assert_eq!(cagr(0.0, 100.0, 1.0), 0.0);
+        assert_eq!(cagr(100.0, -5.0, 1.0), 0.0);
+        assert_eq!(cagr(100.0, 200.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_alpha_beta_levered_benchmark() {
+        let bench = vec![0.01, -0.02, 0.03, 0.005, -0.01];
+        let strategy: Vec<f64> = bench.iter().map(|r| 2.0 * r).collect();
+        let (alpha, beta) = alpha_beta(&strategy, &bench).unwrap();
+        assert!((beta - 2.0).abs() < 1e-9);
+        assert!(alpha.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_alpha_beta_constant_outperformance() {
+        let bench = vec![0.01, -0.01, 0.02];
+        let strategy: Vec<f64> = bench.iter().map(|r| r + 0.005).collect();
+        let (alpha, beta) = alpha_beta(&strategy, &bench).unwrap();
+        assert!((beta - 1.0).abs() < 1e-9);
+        assert!((alpha - 0.005).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_alpha_beta_invalid_inputs() {
+        assert_eq!(alpha_beta(&[], &[]), None);
+        assert_eq!(alpha_beta(&[0.01, 0.02], &[0.01]), None);
+        assert_eq!(alpha_beta(&[0.01, 0.02], &[0.01, 0.01]), None);
+    }
+
+    #[test]
+    fn test_historical_var_quantile() {
+        // Returns -0.10, -0.09, ..., 0.89 in reverse order; the 5th percentile is -0.05
+        let mut returns: Vec<f64> = (0..100).map(|i| (i as f64 - 10.0) / 100.0).collect();
+        returns.reverse();
+        let var95 = historical_var(&returns, 0.95).unwrap();
+        assert!((var95 - 0.05).abs() < 1e-12);
+
+        let var99 = historical_var(&returns, 0.99).unwrap();
+        assert!((var99 - 0.09).abs() < 1e-12);
+        assert!(var99 > var95);
+    }
+
+    #[test]
+    fn test_historical_var_edge_cases() {
+        assert_eq!(historical_var(&[], 0.95), None);
+        // Confidence is clamped, so out-of-range values still pick a valid quantile
+        assert_eq!(historical_var(&[-0.2, 0.1], 1.5), Some(0.2));
+        assert_eq!(historical_var(&[-0.2, 0.1], -1.0), Some(0.0));
+        assert_eq!(historical_var(&[0.01, 0.02], 0.95), Some(0.0));
+    }
+
+    #[test]
+    fn test_historical_volatility_known_dispersion() {
+        // Log returns alternate +1% / -1%: mean 0, sample variance 4e-4 / 3
+        let mut prices = vec![100.0];
+        for i in 0..4 {
+            let r: f64 = if i % 2 == 0 { 0.01 } else { -0.01 };
+            prices.push(prices[i] * r.exp());
+        }
+        let expected = (4e-4_f64 / 3.0).sqrt() * 252.0_f64.sqrt();
+        let vol = historical_volatility(&prices, 252.0).unwrap();
+        assert!((vol - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_historical_volatility_edge_cases() {
+        assert_eq!(historical_volatility(&[100.0, 101.0], 252.0), None);
+        assert_eq!(historical_volatility(&[100.0, 0.0, 101.0], 252.0), None);
+        assert_eq!(historical_volatility(&[50.0, 50.0, 50.0], 252.0), Some(0.0));
+    }
+
+    #[test]
+    fn test_spread_zscore_divergence() {
+        // A tracks 2 * B with a small wobble, then jumps on the last bar
+        let b: Vec<f64> = (0..30).map(|i| 50.0 + i as f64 * 0.5).collect();
+        let mut a: Vec<f64> = b.iter().enumerate().map(|(i, b)| 2.0 * b + if i % 2 == 0 { 0.2 } else { -0.2 }).collect();
+        assert!(spread_zscore(&a, &b, 2.0, 20).unwrap().abs() < 1.5);
+
+        *a.last_mut().unwrap() += 3.0;
+        let z = spread_zscore(&a, &b, 2.0, 20).unwrap();
+        assert!(z > 10.0);
+    }
+
+    #[test]
+    fn test_spread_zscore_edge_cases() {
+        let a = vec![10.0, 11.0, 12.0];
+        let b = vec![5.0, 5.5, 6.0];
+        assert_eq!(spread_zscore(&a, &b, 2.0, 2), None);
+        assert_eq!(spread_zscore(&a, &b, 1.0, 3), None);
+        assert_eq!(spread_zscore(&a, &b, 1.0, 1), None);
+    }
+
+    #[test]
+    fn test_strategy_pair_trade() {
+        let pair = Strategy::new(
+            "MRNA/BNTX",
+            vec![
+                Position {
+                    symbol: "MRNA".to_string(),
+                    quantity: 100.0,
+                    avg_price: 50.0,
+                    current_price: 55.0,
+                },
+                Position {
+                    symbol: "BNTX".to_string(),
+                    quantity: -100.0,
+                    avg_price: 100.0,
+                    current_price: 98.0,
+                },
+            ],
+        );
+        assert_eq!(pair.net_delta(), 0.0);
+        // Long gains 500, short gains 200
+        assert_eq!(pair.strategy_pnl(), 700.0);
+        assert_eq!(Strategy::new("empty", vec![]).strategy_pnl(), 0.0);
+    }
+
+    #[test]
+    fn test_write_reports_csv() {
+        let report = |realized: f64, unrealized: f64| PnLReport {
+            realized_pnl: realized,
+            unrealized_pnl: unrealized,
+            total_pnl: realized + unrealized,
+            return_pct: (realized + unrealized) / 100.0,
+        };
+        let reports = vec![
+            ("MRNA".to_string(), report(100.0, 25.5)),
+            ("BNTX".to_string(), report(-10.126, 0.0)),
+        ];
+
+        let path = std::env::temp_dir().join("pnl_reports_export.csv");
+        let path = path.to_str().unwrap();
+        write_reports_csv(path, &reports).unwrap();
+        let written = fs::read_to_string(path).unwrap();
+        let _ = fs::remove_file(path);
+
+        let lines: Vec<&str> = written.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "symbol,realized_pnl,unrealized_pnl,total_pnl,return_pct");
+        assert_eq!(lines[1], "MRNA,100.00,25.50,125.50,1.25");
+        assert_eq!(lines[2], "BNTX,-10.13,0.00,-10.13,-0.10");
+    }
+
+    #[test]
+    fn test_write_trades_csv() {
+        use crate::trading::OrderSide;
+
+        let trades = vec![Trade {
+            id: 1,
+            symbol: "MRNA".to_string(),
+            side: OrderSide::Buy,
+            quantity: 10.0,
+            price: 55.5,
+            timestamp: 42,
+            signal_id: None,
+        }];
+        let path = std::env::temp_dir().join("pnl_trades_export.csv");
+        let path = path.to_str().unwrap();
+        write_trades_csv(path, &trades).unwrap();
+        let written = fs::read_to_string(path).unwrap();
+        let _ = fs::remove_file(path);
+
+        assert_eq!(written, format!("{}\n1,MRNA,Buy,10.00,55.50,42\n", TRADES_CSV_HEADER));
+        assert!(write_trades_csv("/nonexistent-dir/trades.csv", &trades).is_err());
+    }
+
+    #[test]
+    fn test_pnl_calculator_snapshot_restore() {
+        let mut calc = PnLCalculator::new(10_000.0);
+        calc.add_realized_pnl(250.0);
+        let snap = calc.snapshot();
+
+        calc.add_realized_pnl(-1_000.0);
+        assert_eq!(calc.calculate_report(&[]).realized_pnl, -750.0);
+        calc.restore(snap);
+        assert_eq!(calc.calculate_report(&[]).realized_pnl, 250.0);
+
+        calc.reset();
+        let report = calc.calculate_report(&[]);
+        assert_eq!(report.realized_pnl, 0.0);
+        assert_eq!(calc.initial_capital(), 10_000.0);
+    }
+
+    #[test]
+    fn test_correlation_matrix() {
+        let base = vec![100.0, 102.0, 101.0, 105.0, 104.0];
+        let returns = simple_returns(&base);
+        // Rebuild a series whose returns are exactly the negation of base's
+        let mut inverted = vec![100.0];
+        for r in &returns {
+            let last = *inverted.last().unwrap();
+            inverted.push(last * (1.0 - r));
+        }
+        let series = vec![
+            ("MRNA".to_string(), base.clone()),
+            ("COPY".to_string(), base),
+            ("INV".to_string(), inverted),
+            ("FLAT".to_string(), vec![50.0; 6]),
+        ];
+
+        let matrix = correlation_matrix(&series);
+        assert_eq!(matrix.len(), 4);
+        assert!((matrix[0][1] - 1.0).abs() < 1e-9);
+        assert!((matrix[0][2] + 1.0).abs() < 1e-9);
+        assert_eq!(matrix[0][3], 0.0);
+        for (i, row) in matrix.iter().enumerate() {
+            assert_eq!(row[i], 1.0);
+            for (j, &value) in row.iter().enumerate() {
+                assert_eq!(value, matrix[j][i]);
+            }
+        }
+        assert!(correlation_matrix(&[]).is_empty());
+    }
 }
@@ -0,0 +1,86 @@
+// Saved State
+// Versioned wrapper for anything written to disk, with step-by-step upgrades
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const SAVED_STATE_VERSION: u32 = 2;
+
+// MIGRATIONS[i] upgrades a payload from version i + 1 to i + 2
+type Migration = fn(Value) -> Result<Value, String>;
+const MIGRATIONS: [Migration; 1] = [v1_to_v2];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedState {
+    pub schema_version: u32,
+    pub payload: Value,
+}
+
+impl SavedState {
+    pub fn new<T: Serialize>(payload: &T) -> Result<Self, String> {
+        let payload = serde_json::to_value(payload)
+            .map_err(|e| format!("Failed to serialize saved state: {}", e))?;
+        Ok(SavedState {
+            schema_version: SAVED_STATE_VERSION,
+            payload,
+        })
+    }
+
+    pub fn payload_as<T: DeserializeOwned>(&self) -> Result<T, String> {
+        serde_json::from_value(self.payload.clone())
+            .map_err(|e| format!("Failed to parse saved state payload: {}", e))
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string(self).map_err(|e| format!("Failed to serialize saved state: {}", e))
+    }
+}
+
+// Parse a saved blob and run it through every migration between its version and
+// the current one. Versions newer than this build (or 0) are rejected
+pub fn migrate(raw: &str) -> Result<SavedState, String> {
+    let mut state: SavedState =
+        serde_json::from_str(raw).map_err(|e| format!("Failed to parse saved state: {}", e))?;
+    if state.schema_version == 0 || state.schema_version > SAVED_STATE_VERSION {
+        return Err(format!(
+            "Unsupported saved state schema_version {} (expected at most {})",
+            state.schema_version, SAVED_STATE_VERSION
+        ));
+    }
+    for migration in &MIGRATIONS[state.schema_version as usize - 1..] {
+        state.payload = migration(state.payload)?;
+        state.schema_version += 1;
+    }
+    Ok(state)
+}
+
+// Nothing changed in the payload layout; kept so later migrations have a pattern to follow
+fn v1_to_v2(payload: Value) -> Result<Value, String> {
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::momentum::IndicatorConfig;
+
+    #[test]
+    fn test_migrate_version_one_blob() {
+        let raw = r#"{"schema_version":1,"payload":{"rsi_period":9,"sma_period":20,"bollinger_period":20,"bollinger_std":2.0,"atr_period":14}}"#;
+        let state = migrate(raw).unwrap();
+        assert_eq!(state.schema_version, SAVED_STATE_VERSION);
+        let config: IndicatorConfig = state.payload_as().unwrap();
+        assert_eq!(config.rsi_period, 9);
+    }
+
+    #[test]
+    fn test_saved_state_round_trip_and_future_version() {
+        let state = SavedState::new(&IndicatorConfig::default()).unwrap();
+        assert_eq!(migrate(&state.to_json().unwrap()).unwrap(), state);
+
+        let err = migrate(r#"{"schema_version":3,"payload":null}"#).unwrap_err();
+        assert!(err.contains("Unsupported saved state schema_version 3"));
+        assert!(migrate(r#"{"schema_version":0,"payload":null}"#).is_err());
+    }
+}
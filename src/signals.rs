@@ -1,7 +1,12 @@
 // Trading Signals
 // Buy/Sell signal generation
 
-#[derive(Debug, Clone, PartialEq)]
+use std::collections::HashMap;
+
+use crate::clock::Clock;
+use crate::momentum::{calculate_sma, rsi_series};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum SignalType {
     Buy,
     Sell,
@@ -10,22 +15,42 @@ pub enum SignalType {
 
 #[derive(Debug, Clone)]
 pub struct TradingSignal {
+    // 0 until stamped by TradingSystem::add_signal
+    pub id: u64,
     pub signal_type: SignalType,
     pub symbol: String,
     pub strength: f64,
     pub reason: String,
+    // Clock time (ms) after which the signal should no longer be acted on
+    pub expires_at: Option<u64>,
 }
 
 impl TradingSignal {
     pub fn new(signal_type: SignalType, symbol: &str, strength: f64, reason: &str) -> Self {
         TradingSignal {
+            id: 0,
             signal_type,
             symbol: symbol.to_string(),
             strength,
             reason: reason.to_string(),
+            expires_at: None,
         }
     }
 
+    pub fn with_id(mut self, id: u64) -> Self {
+        self.id = id;
+        self
+    }
+
+    pub fn with_expiry(mut self, expires_at_ms: u64) -> Self {
+        self.expires_at = Some(expires_at_ms);
+        self
+    }
+
+    pub fn is_expired(&self, clock: &dyn Clock) -> bool {
+        self.expires_at.is_some_and(|expires_at| clock.now_ms() > expires_at)
+    }
+
     pub fn display(&self) -> String {
         format!(
             "{:?} {} (strength: {:.2}) - {}",
@@ -34,6 +59,48 @@ impl TradingSignal {
     }
 }
 
+// One signal per line, strongest first; equal strengths keep their input order
+pub fn display_signals(signals: &[TradingSignal]) -> String {
+    let mut ranked: Vec<&TradingSignal> = signals.iter().collect();
+    ranked.sort_by(|a, b| b.strength.total_cmp(&a.strength));
+    ranked.iter().map(|s| s.display()).collect::<Vec<_>>().join("\n")
+}
+
+// First signal with the highest strength
+pub fn strongest(signals: &[TradingSignal]) -> Option<&TradingSignal> {
+    signals
+        .iter()
+        .reduce(|best, s| if s.strength > best.strength { s } else { best })
+}
+
+// Drops a signal that repeats the last allowed one for its symbol within `cooldown`
+// time units; a change of signal type always passes
+#[derive(Debug, Clone)]
+pub struct SignalGate {
+    pub cooldown: u64,
+    last_allowed: HashMap<String, (SignalType, u64)>,
+}
+
+impl SignalGate {
+    pub fn new(cooldown: u64) -> Self {
+        SignalGate {
+            cooldown,
+            last_allowed: HashMap::new(),
+        }
+    }
+
+    // Suppressed repeats don't extend the cooldown, so a steady signal passes once per period
+    pub fn allow(&mut self, symbol: &str, signal: &SignalType, now: u64) -> bool {
+        if let Some((last_signal, at)) = self.last_allowed.get(symbol) {
+            if last_signal == signal && now.saturating_sub(*at) < self.cooldown {
+                return false;
+            }
+        }
+        self.last_allowed.insert(symbol.to_string(), (signal.clone(), now));
+        true
+    }
+}
+
 pub fn generate_signals(rsi: f64, macd: f64) -> SignalType {
     if rsi < 30.0 && macd > 0.0 {
         SignalType::Buy
@@ -44,9 +111,114 @@ pub fn generate_signals(rsi: f64, macd: f64) -> SignalType {
     }
 }
 
+// Buy when the fast SMA crosses above the slow one on the latest bar, Sell when it
+// crosses below; needs slow + 1 prices to see both sides of the cross
+pub fn detect_crossover(prices: &[f64], fast: usize, slow: usize) -> Option<SignalType> {
+    if fast == 0 || fast >= slow || prices.len() < slow + 1 {
+        return None;
+    }
+    let fast_sma = calculate_sma(prices, fast);
+    let slow_sma = calculate_sma(prices, slow);
+    let (fast_prev, fast_now) = (fast_sma[fast_sma.len() - 2], fast_sma[fast_sma.len() - 1]);
+    let (slow_prev, slow_now) = (slow_sma[slow_sma.len() - 2], slow_sma[slow_sma.len() - 1]);
+
+    if fast_prev <= slow_prev && fast_now > slow_now {
+        Some(SignalType::Buy)
+    } else if fast_prev >= slow_prev && fast_now < slow_now {
+        Some(SignalType::Sell)
+    } else {
+        None
+    }
+}
+
+// Compares the last two price swings against RSI at the same bars:
+// a higher price high on a lower RSI high is bearish, the mirror at the lows is bullish
+pub fn detect_divergence(prices: &[f64], period: usize) -> Option<SignalType> {
+    let rsi = rsi_series(prices, period);
+    if rsi.is_empty() {
+        return None;
+    }
+
+    let mut highs = Vec::new();
+    let mut lows = Vec::new();
+    for i in period.max(1)..prices.len() - 1 {
+        if prices[i] > prices[i - 1] && prices[i] >= prices[i + 1] {
+            highs.push(i);
+        } else if prices[i] < prices[i - 1] && prices[i] <= prices[i + 1] {
+            lows.push(i);
+        }
+    }
+
+    let bearish = match highs[..] {
+        [.., a, b] if prices[b] > prices[a] && rsi[b - period] < rsi[a - period] => Some(b),
+        _ => None,
+    };
+    let bullish = match lows[..] {
+        [.., a, b] if prices[b] < prices[a] && rsi[b - period] > rsi[a - period] => Some(b),
+        _ => None,
+    };
+
+    // If both fire, the more recent swing wins
+    match (bearish, bullish) {
+        (Some(high), Some(low)) if low > high => Some(SignalType::Buy),
+        (Some(_), _) => Some(SignalType::Sell),
+        (None, Some(_)) => Some(SignalType::Buy),
+        (None, None) => None,
+    }
+}
+
+// Fraction of Buy/Sell signals the price agreed with `horizon` ticks later.
+// Hold signals, and signals without a price at both ends, aren't scored
+pub fn score_signals(
+    signals: &[(u64, TradingSignal)],
+    price_at: impl Fn(u64) -> Option<f64>,
+    horizon: u64,
+) -> HashMap<SignalType, f64> {
+    let mut tallies: HashMap<SignalType, (usize, usize)> = HashMap::new();
+
+    for (timestamp, signal) in signals {
+        if signal.signal_type == SignalType::Hold {
+            continue;
+        }
+        let (Some(entry), Some(exit)) = (price_at(*timestamp), price_at(timestamp + horizon)) else {
+            continue;
+        };
+        let hit = match signal.signal_type {
+            SignalType::Buy => exit > entry,
+            SignalType::Sell => exit < entry,
+            SignalType::Hold => false,
+        };
+        let tally = tallies.entry(signal.signal_type.clone()).or_insert((0, 0));
+        tally.1 += 1;
+        if hit {
+            tally.0 += 1;
+        }
+    }
+
+    tallies
+        .into_iter()
+        .map(|(signal_type, (hits, total))| (signal_type, hits as f64 / total as f64))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clock::MockClock;
+
+    #[test]
+    fn test_signal_expires_with_mock_clock() {
+        let mut clock = MockClock::new(10_000);
+        let signal = TradingSignal::new(SignalType::Buy, "MRNA", 0.8, "Breakout")
+            .with_expiry(clock.now_ms() + 5_000);
+        assert!(!signal.is_expired(&clock));
+
+        clock.advance(5_000);
+        assert!(!signal.is_expired(&clock));
+        clock.advance(1);
+        assert!(signal.is_expired(&clock));
+        assert!(!TradingSignal::new(SignalType::Hold, "MRNA", 0.0, "").is_expired(&clock));
+    }
 
     #[test]
     fn test_trading_signal_new() {
@@ -67,6 +239,45 @@ mod tests {
         assert!(display.contains("Overbought"));
     }
 
+    #[test]
+    fn test_display_signals_sorted_by_strength() {
+        let signals = vec![
+            TradingSignal::new(SignalType::Hold, "AAA", 0.2, "weak"),
+            TradingSignal::new(SignalType::Buy, "BBB", 0.9, "strong"),
+            TradingSignal::new(SignalType::Sell, "CCC", 0.5, "medium"),
+        ];
+        assert_eq!(strongest(&signals).unwrap().symbol, "BBB");
+
+        let rendered = display_signals(&signals);
+        let symbols: Vec<&str> = rendered.lines().map(|l| l.split_whitespace().nth(1).unwrap()).collect();
+        assert_eq!(symbols, vec!["BBB", "CCC", "AAA"]);
+    }
+
+    #[test]
+    fn test_strongest_ties_and_empty() {
+        let signals = vec![
+            TradingSignal::new(SignalType::Buy, "FIRST", 0.7, ""),
+            TradingSignal::new(SignalType::Buy, "SECOND", 0.7, ""),
+        ];
+        assert_eq!(strongest(&signals).unwrap().symbol, "FIRST");
+        assert!(display_signals(&signals).starts_with("Buy FIRST"));
+        assert!(strongest(&[]).is_none());
+        assert_eq!(display_signals(&[]), "");
+    }
+
+    #[test]
+    fn test_signal_gate_cooldown() {
+        let mut gate = SignalGate::new(10);
+        assert!(gate.allow("MRNA", &SignalType::Buy, 100));
+        assert!(!gate.allow("MRNA", &SignalType::Buy, 105));
+        // Other symbols and a change of direction aren't held back
+        assert!(gate.allow("BNTX", &SignalType::Buy, 105));
+        assert!(gate.allow("MRNA", &SignalType::Sell, 106));
+        assert!(gate.allow("MRNA", &SignalType::Buy, 107));
+        assert!(!gate.allow("MRNA", &SignalType::Buy, 116));
+        assert!(gate.allow("MRNA", &SignalType::Buy, 117));
+    }
+
     #[test]
     fn test_generate_signals_buy() {
         let signal = generate_signals(25.0, 1.0);
@@ -96,4 +307,53 @@ mod tests {
         let signal = generate_signals(75.0, 1.0);
         assert_eq!(signal, SignalType::Hold);
     }
+
+    #[test]
+    fn test_detect_crossover() {
+        assert_eq!(detect_crossover(&[10.0, 10.0, 10.0, 10.0, 11.0], 2, 4), Some(SignalType::Buy));
+        assert_eq!(detect_crossover(&[10.0, 10.0, 10.0, 10.0, 9.0], 2, 4), Some(SignalType::Sell));
+        // Already above: no fresh cross
+        assert_eq!(detect_crossover(&[10.0, 11.0, 12.0, 13.0, 14.0], 2, 4), None);
+        assert_eq!(detect_crossover(&[10.0, 11.0], 2, 4), None);
+        assert_eq!(detect_crossover(&[10.0; 10], 4, 2), None);
+    }
+
+    #[test]
+    fn test_detect_bearish_divergence() {
+        // Second high (15.5) tops the first (15) but comes after a pullback, so RSI is weaker
+        let prices = vec![10.0, 11.0, 12.0, 15.0, 13.0, 12.0, 11.0, 15.5, 14.0];
+        assert_eq!(detect_divergence(&prices, 3), Some(SignalType::Sell));
+    }
+
+    #[test]
+    fn test_detect_bullish_divergence() {
+        let prices = vec![20.0, 19.0, 18.0, 15.0, 17.0, 18.0, 19.0, 14.5, 16.0];
+        assert_eq!(detect_divergence(&prices, 3), Some(SignalType::Buy));
+    }
+
+    #[test]
+    fn test_detect_divergence_none() {
+        let trending: Vec<f64> = (0..20).map(|i| 100.0 + i as f64).collect();
+        assert_eq!(detect_divergence(&trending, 3), None);
+        assert_eq!(detect_divergence(&[1.0, 2.0], 3), None);
+    }
+
+    #[test]
+    fn test_score_signals_hit_rate() {
+        let prices = [100.0, 105.0, 103.0, 101.0];
+        let price_at = |t: u64| prices.get(t as usize).copied();
+        let signals = vec![
+            (0, TradingSignal::new(SignalType::Buy, "TEST", 0.8, "right")),
+            (1, TradingSignal::new(SignalType::Buy, "TEST", 0.8, "wrong")),
+            (1, TradingSignal::new(SignalType::Sell, "TEST", 0.8, "right")),
+            (2, TradingSignal::new(SignalType::Hold, "TEST", 0.1, "ignored")),
+            // No price one tick after the last bar, so it can't be scored
+            (3, TradingSignal::new(SignalType::Sell, "TEST", 0.8, "unscored")),
+        ];
+
+        let scores = score_signals(&signals, price_at, 1);
+        assert_eq!(scores.get(&SignalType::Buy), Some(&0.5));
+        assert_eq!(scores.get(&SignalType::Sell), Some(&1.0));
+        assert!(!scores.contains_key(&SignalType::Hold));
+    }
 }
@@ -0,0 +1,57 @@
+// Monte-Carlo Simulation
+// Synthetic price paths for stress testing P&L and risk code
+
+use crate::rng::Rng;
+
+// Geometric Brownian motion with per-step drift and volatility (dt = 1).
+// Returns steps + 1 prices, starting with `start`.
+pub fn simulate_gbm(start: f64, drift: f64, vol: f64, steps: usize, rng: &mut Rng) -> Vec<f64> {
+    let mut path = Vec::with_capacity(steps + 1);
+    let mut price = start;
+    path.push(price);
+
+    let drift_term = drift - 0.5 * vol * vol;
+    for _ in 0..steps {
+        let shock = if vol == 0.0 { 0.0 } else { vol * rng.next_gaussian() };
+        price *= (drift_term + shock).exp();
+        path.push(price);
+    }
+
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zero_vol_grows_at_drift() {
+        let mut rng = Rng::new(1);
+        let path = simulate_gbm(100.0, 0.01, 0.0, 10, &mut rng);
+        assert_eq!(path.len(), 11);
+        for (t, price) in path.iter().enumerate() {
+            let expected = 100.0 * (0.01 * t as f64).exp();
+            assert!((price - expected).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_fixed_seed_reproducible() {
+        let a = simulate_gbm(50.0, 0.0005, 0.02, 250, &mut Rng::new(7));
+        let b = simulate_gbm(50.0, 0.0005, 0.02, 250, &mut Rng::new(7));
+        let c = simulate_gbm(50.0, 0.0005, 0.02, 250, &mut Rng::new(8));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_prices_stay_positive() {
+        let path = simulate_gbm(10.0, -0.01, 0.5, 500, &mut Rng::new(3));
+        assert!(path.iter().all(|&p| p > 0.0));
+    }
+
+    #[test]
+    fn test_zero_steps() {
+        assert_eq!(simulate_gbm(100.0, 0.1, 0.2, 0, &mut Rng::new(0)), vec![100.0]);
+    }
+}
@@ -0,0 +1,182 @@
+// Position Sizing
+// How much to trade given capital and a risk budget
+
+use std::collections::HashMap;
+
+use crate::trading::{Order, OrderSide, OrderType};
+use crate::trading_models::Position;
+
+// Whole shares risking `risk_fraction` of capital between entry and stop
+pub fn size_by_risk(capital: f64, risk_fraction: f64, entry: f64, stop: f64) -> f64 {
+    size_by_risk_fractional(capital, risk_fraction, entry, stop).floor()
+}
+
+// Same as size_by_risk but allows fractional quantities
+pub fn size_by_risk_fractional(capital: f64, risk_fraction: f64, entry: f64, stop: f64) -> f64 {
+    let stop_distance = (entry - stop).abs();
+    if stop_distance == 0.0 || capital <= 0.0 || risk_fraction <= 0.0 {
+        return 0.0;
+    }
+    (capital * risk_fraction) / stop_distance
+}
+
+// Kelly fraction of capital to stake, clamped to [0, 1]: no leverage and no negative bets
+pub fn kelly_fraction(win_prob: f64, win_loss_ratio: f64) -> f64 {
+    if win_loss_ratio <= 0.0 {
+        return 0.0;
+    }
+    (win_prob - (1.0 - win_prob) / win_loss_ratio).clamp(0.0, 1.0)
+}
+
+// Half Kelly gives up some growth for much lower variance
+pub fn half_kelly(win_prob: f64, win_loss_ratio: f64) -> f64 {
+    kelly_fraction(win_prob, win_loss_ratio) / 2.0
+}
+
+// Market orders moving each targeted symbol to its weight of total_equity, in whole
+// shares. A target of 0 sells the whole position; symbols without a target or a
+// known price are left alone
+pub fn rebalance(
+    positions: &[Position],
+    targets: &HashMap<String, f64>,
+    total_equity: f64,
+    prices: &HashMap<String, f64>,
+) -> Vec<Order> {
+    let mut symbols: Vec<&String> = targets.keys().collect();
+    symbols.sort();
+
+    symbols
+        .into_iter()
+        .filter_map(|symbol| {
+            let current = positions
+                .iter()
+                .filter(|p| &p.symbol == symbol)
+                .map(|p| p.quantity)
+                .sum::<f64>();
+            let target_weight = targets[symbol];
+            let desired = if target_weight <= 0.0 {
+                0.0
+            } else {
+                let price = *prices.get(symbol).filter(|&&price| price > 0.0)?;
+                (target_weight * total_equity / price).round()
+            };
+
+            // Exits close the exact holding so a fractional position can't flip short
+            let delta = if desired == 0.0 { -current } else { (desired - current).round() };
+            if delta == 0.0 {
+                return None;
+            }
+            let side = if delta > 0.0 { OrderSide::Buy } else { OrderSide::Sell };
+            Some(Order::new(symbol, side, OrderType::Market, delta.abs()))
+        })
+        .collect()
+}
+
+// Market order in the hedge instrument offsetting `beta` times the position's market
+// value, on the opposite side, in whole shares. A non-positive hedge price yields a
+// zero-quantity order
+pub fn hedge_order(position: &Position, hedge_symbol: &str, hedge_price: f64, beta: f64) -> Order {
+    let notional = position.market_value() * beta;
+    let exposure_side = if notional >= 0.0 { OrderSide::Buy } else { OrderSide::Sell };
+    let quantity = if hedge_price > 0.0 { (notional.abs() / hedge_price).round() } else { 0.0 };
+    Order::new(hedge_symbol, exposure_side.flip(), OrderType::Market, quantity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_size_by_risk_one_percent() {
+        assert_eq!(size_by_risk(1_000_000.0, 0.01, 50.0, 48.0), 5000.0);
+    }
+
+    #[test]
+    fn test_size_by_risk_short_side() {
+        // Stop above entry for a short uses the same distance
+        assert_eq!(size_by_risk(1_000_000.0, 0.01, 48.0, 50.0), 5000.0);
+    }
+
+    #[test]
+    fn test_size_by_risk_floors_to_whole_shares() {
+        assert_eq!(size_by_risk(10_000.0, 0.01, 10.0, 7.0), 33.0);
+        let fractional = size_by_risk_fractional(10_000.0, 0.01, 10.0, 7.0);
+        assert!((fractional - 33.333_333).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_size_by_risk_zero_stop_distance() {
+        assert_eq!(size_by_risk(1_000_000.0, 0.01, 50.0, 50.0), 0.0);
+        assert_eq!(size_by_risk_fractional(1_000_000.0, 0.01, 50.0, 50.0), 0.0);
+    }
+
+    #[test]
+    fn test_kelly_fraction_positive_edge() {
+        assert!((kelly_fraction(0.6, 2.0) - 0.4).abs() < 1e-12);
+        assert!((half_kelly(0.6, 2.0) - 0.2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_kelly_fraction_clamps() {
+        // Negative edge: don't bet at all
+        assert_eq!(kelly_fraction(0.3, 1.0), 0.0);
+        assert_eq!(kelly_fraction(0.6, 0.0), 0.0);
+        assert_eq!(kelly_fraction(0.6, -1.0), 0.0);
+        assert_eq!(kelly_fraction(1.5, 2.0), 1.0);
+    }
+
+    fn holding(symbol: &str, quantity: f64, price: f64) -> Position {
+        Position {
+            symbol: symbol.to_string(),
+            quantity,
+            avg_price: price,
+            current_price: price,
+        }
+    }
+
+    #[test]
+    fn test_rebalance_eighty_twenty_to_even() {
+        let positions = vec![holding("AAA", 80.0, 100.0), holding("BBB", 20.0, 100.0)];
+        let targets = HashMap::from([("AAA".to_string(), 0.5), ("BBB".to_string(), 0.5)]);
+        let prices = HashMap::from([("AAA".to_string(), 100.0), ("BBB".to_string(), 100.0)]);
+
+        let orders = rebalance(&positions, &targets, 10_000.0, &prices);
+        assert_eq!(orders.len(), 2);
+        assert_eq!((orders[0].symbol.as_str(), orders[0].side, orders[0].quantity), ("AAA", OrderSide::Sell, 30.0));
+        assert_eq!((orders[1].symbol.as_str(), orders[1].side, orders[1].quantity), ("BBB", OrderSide::Buy, 30.0));
+        assert!(orders.iter().all(|o| o.order_type == OrderType::Market));
+    }
+
+    #[test]
+    fn test_rebalance_zero_target_and_rounding() {
+        let positions = vec![holding("AAA", 12.5, 40.0), holding("BBB", 10.0, 30.0)];
+        // No price for AAA is needed to exit it completely
+        let targets = HashMap::from([("AAA".to_string(), 0.0), ("BBB".to_string(), 0.5)]);
+        let prices = HashMap::from([("BBB".to_string(), 30.0)]);
+
+        let orders = rebalance(&positions, &targets, 1_000.0, &prices);
+        assert_eq!(orders[0].side, OrderSide::Sell);
+        assert_eq!(orders[0].quantity, 12.5);
+        // 500 / 30 = 16.67 -> 17 shares, holding 10
+        assert_eq!((orders[1].side, orders[1].quantity), (OrderSide::Buy, 7.0));
+    }
+
+    #[test]
+    fn test_hedge_order_long_with_beta() {
+        // $100k long, beta 1.5 -> $150k of SPY at $450 = 333.33 -> 333 shares
+        let position = holding("MRNA", 1_000.0, 100.0);
+        let order = hedge_order(&position, "SPY", 450.0, 1.5);
+        assert_eq!(order.symbol, "SPY");
+        assert_eq!(order.side, OrderSide::Sell);
+        assert_eq!(order.order_type, OrderType::Market);
+        assert_eq!(order.quantity, 333.0);
+    }
+
+    #[test]
+    fn test_hedge_order_short_buys() {
+        let position = holding("MRNA", -500.0, 100.0);
+        let order = hedge_order(&position, "SPY", 500.0, 1.0);
+        assert_eq!((order.side, order.quantity), (OrderSide::Buy, 100.0));
+        assert_eq!(hedge_order(&position, "SPY", 0.0, 1.0).quantity, 0.0);
+    }
+}
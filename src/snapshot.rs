@@ -0,0 +1,15 @@
+// Snapshots
+// Uniform reset and checkpoint/restore for stateful components
+
+// Return a component to its freshly constructed state
+pub trait Resettable {
+    fn reset(&mut self);
+}
+
+// Capture accumulated state so it can be put back later, e.g. between backtest runs
+pub trait Snapshot {
+    type Snap;
+
+    fn snapshot(&self) -> Self::Snap;
+    fn restore(&mut self, snap: Self::Snap);
+}
@@ -1,16 +1,186 @@
 // Storm Topologies
 // Distributed stream processing topologies
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::time::Instant;
+
+use crate::clock::Clock;
+use crate::rng::Rng;
+use crate::snapshot::{Resettable, Snapshot};
 
 // Bolt: Processing unit in Storm topology
 pub trait Bolt {
     fn execute(&mut self, input: &str) -> Vec<String>;
 }
 
+// Run input through each bolt in turn, feeding the first tuple one bolt emits to
+// the next. Stops early (returning nothing) if a bolt emits no tuples
+pub fn chain_bolts(mut bolts: Vec<Box<dyn Bolt>>, input: &str) -> Vec<String> {
+    let mut output = vec![input.to_string()];
+    for bolt in bolts.iter_mut() {
+        let Some(next) = output.first() else {
+            return Vec::new();
+        };
+        output = bolt.execute(next);
+    }
+    output
+}
+
+// Upper bounds (exclusive) of the latency histogram buckets: <1µs, <10µs, ... <1s, then everything slower
+pub const LATENCY_BUCKETS_NANOS: [u128; 8] = [
+    1_000,
+    10_000,
+    100_000,
+    1_000_000,
+    10_000_000,
+    100_000_000,
+    1_000_000_000,
+    u128::MAX,
+];
+
+// Call counts and execute latencies for a MeteredBolt
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BoltMetrics {
+    pub executions: u64,
+    pub tuples_emitted: u64,
+    pub total_nanos: u128,
+    pub max_nanos: u128,
+    histogram: [u64; LATENCY_BUCKETS_NANOS.len()],
+}
+
+impl BoltMetrics {
+    pub fn record(&mut self, nanos: u128, emitted: usize) {
+        self.executions += 1;
+        self.tuples_emitted += emitted as u64;
+        self.total_nanos += nanos;
+        self.max_nanos = self.max_nanos.max(nanos);
+        let bucket = LATENCY_BUCKETS_NANOS.iter().position(|&bound| nanos < bound).unwrap_or(LATENCY_BUCKETS_NANOS.len() - 1);
+        self.histogram[bucket] += 1;
+    }
+
+    // Executions per bucket, aligned with LATENCY_BUCKETS_NANOS
+    pub fn histogram(&self) -> &[u64] {
+        &self.histogram
+    }
+
+    pub fn mean_nanos(&self) -> u128 {
+        if self.executions == 0 {
+            return 0;
+        }
+        self.total_nanos / self.executions as u128
+    }
+
+    // Upper bound of the bucket holding the 99th percentile, capped at the slowest
+    // execute seen. Bucket resolution makes this an estimate, never an understatement
+    pub fn p99_nanos(&self) -> u128 {
+        let target = (self.executions * 99).div_ceil(100);
+        let mut seen = 0;
+        for (count, bound) in self.histogram.iter().zip(LATENCY_BUCKETS_NANOS) {
+            seen += count;
+            if seen >= target && seen > 0 {
+                return bound.min(self.max_nanos);
+            }
+        }
+        0
+    }
+}
+
+// Wraps any bolt and times every execute
+pub struct MeteredBolt<B: Bolt> {
+    inner: B,
+    metrics: BoltMetrics,
+}
+
+impl<B: Bolt> MeteredBolt<B> {
+    pub fn new(inner: B) -> Self {
+        MeteredBolt {
+            inner,
+            metrics: BoltMetrics::default(),
+        }
+    }
+
+    pub fn metrics(&self) -> &BoltMetrics {
+        &self.metrics
+    }
+
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+}
+
+impl<B: Bolt> Bolt for MeteredBolt<B> {
+    fn execute(&mut self, input: &str) -> Vec<String> {
+        let started = Instant::now();
+        let output = self.inner.execute(input);
+        self.metrics.record(started.elapsed().as_nanos(), output.len());
+        output
+    }
+}
+
+// Filter Topology: drop stop words before downstream processing
+pub struct FilterBolt {
+    stop_words: Vec<String>,
+}
+
+impl FilterBolt {
+    pub fn new(stop_words: &[&str]) -> Self {
+        FilterBolt {
+            stop_words: stop_words.iter().map(|w| w.to_lowercase()).collect(),
+        }
+    }
+}
+
+impl Bolt for FilterBolt {
+    fn execute(&mut self, input: &str) -> Vec<String> {
+        let kept: Vec<&str> = input
+            .split_whitespace()
+            .filter(|word| !self.stop_words.contains(&word.to_lowercase()))
+            .collect();
+        vec![kept.join(" ")]
+    }
+}
+
 // Word Count Topology
+// How WordCountBolt splits its input into words
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TokenizeMode {
+    #[default]
+    Whitespace,
+    // Any non-alphanumeric character ends a word: "don't" -> "don", "t"
+    Alphanumeric,
+    // As Alphanumeric, but an apostrophe between two alphanumerics stays in the word
+    AlphanumericWithApostrophe,
+}
+
+pub fn tokenize(input: &str, mode: TokenizeMode) -> Vec<&str> {
+    if mode == TokenizeMode::Whitespace {
+        return input.split_whitespace().collect();
+    }
+    let keep_apostrophes = mode == TokenizeMode::AlphanumericWithApostrophe;
+    let chars: Vec<(usize, char)> = input.char_indices().collect();
+    let mut tokens = Vec::new();
+    let mut start: Option<usize> = None;
+
+    for (i, &(pos, c)) in chars.iter().enumerate() {
+        let inner_apostrophe = keep_apostrophes
+            && c == '\''
+            && start.is_some()
+            && chars.get(i + 1).is_some_and(|&(_, next)| next.is_alphanumeric());
+        if c.is_alphanumeric() || inner_apostrophe {
+            start.get_or_insert(pos);
+        } else if let Some(word_start) = start.take() {
+            tokens.push(&input[word_start..pos]);
+        }
+    }
+    if let Some(word_start) = start {
+        tokens.push(&input[word_start..]);
+    }
+    tokens
+}
+
 pub struct WordCountBolt {
     counts: HashMap<String, usize>,
+    mode: TokenizeMode,
 }
 
 impl Default for WordCountBolt {
@@ -21,11 +191,20 @@ impl Default for WordCountBolt {
 
 impl WordCountBolt {
     pub fn new() -> Self {
+        Self::with_mode(TokenizeMode::default())
+    }
+
+    pub fn with_mode(mode: TokenizeMode) -> Self {
         WordCountBolt {
             counts: HashMap::new(),
+            mode,
         }
     }
 
+    pub fn mode(&self) -> TokenizeMode {
+        self.mode
+    }
+
     pub fn get_counts(&self) -> &HashMap<String, usize> {
         &self.counts
     }
@@ -33,13 +212,31 @@ impl WordCountBolt {
 
 impl Bolt for WordCountBolt {
     fn execute(&mut self, input: &str) -> Vec<String> {
-        for word in input.split_whitespace() {
+        for word in tokenize(input, self.mode) {
             *self.counts.entry(word.to_lowercase()).or_insert(0) += 1;
         }
         vec![format!("Processed: {}", input)]
     }
 }
 
+impl Resettable for WordCountBolt {
+    fn reset(&mut self) {
+        self.counts.clear();
+    }
+}
+
+impl Snapshot for WordCountBolt {
+    type Snap = HashMap<String, usize>;
+
+    fn snapshot(&self) -> Self::Snap {
+        self.counts.clone()
+    }
+
+    fn restore(&mut self, snap: Self::Snap) {
+        self.counts = snap;
+    }
+}
+
 // Sum Topology
 pub struct SumBolt {
     total: f64,
@@ -61,17 +258,218 @@ impl SumBolt {
     }
 }
 
+impl SumBolt {
+    // Add a number to the total, returning the new total; bad input leaves it unchanged
+    pub fn try_execute(&mut self, input: &str) -> Result<f64, String> {
+        let token = input.trim();
+        let num = token
+            .parse::<f64>()
+            .map_err(|e| format!("Invalid number '{}': {}", token, e))?;
+        self.total += num;
+        Ok(self.total)
+    }
+}
+
 impl Bolt for SumBolt {
+    fn execute(&mut self, input: &str) -> Vec<String> {
+        match self.try_execute(input) {
+            Ok(total) => vec![format!("Sum: {}", total)],
+            Err(message) => vec![message],
+        }
+    }
+}
+
+impl Resettable for SumBolt {
+    fn reset(&mut self) {
+        self.total = 0.0;
+    }
+}
+
+impl Snapshot for SumBolt {
+    type Snap = f64;
+
+    fn snapshot(&self) -> Self::Snap {
+        self.total
+    }
+
+    fn restore(&mut self, snap: Self::Snap) {
+        self.total = snap;
+    }
+}
+
+// Stats Topology: keeps every number so the distribution can be summarized
+pub struct StatsBolt {
+    values: Vec<f64>,
+}
+
+impl Default for StatsBolt {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StatsBolt {
+    pub fn new() -> Self {
+        StatsBolt { values: Vec::new() }
+    }
+
+    pub fn count(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        if self.values.is_empty() {
+            return None;
+        }
+        Some(self.values.iter().sum::<f64>() / self.values.len() as f64)
+    }
+
+    pub fn median(&self) -> Option<f64> {
+        self.percentile(50.0)
+    }
+
+    // Linear interpolation between closest ranks; p is in [0, 100] and clamped
+    pub fn percentile(&self, p: f64) -> Option<f64> {
+        if self.values.is_empty() {
+            return None;
+        }
+        let mut sorted = self.values.clone();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let rank = p.clamp(0.0, 100.0) / 100.0 * (sorted.len() - 1) as f64;
+        let lower = rank.floor() as usize;
+        let upper = rank.ceil() as usize;
+        Some(sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64))
+    }
+
+    pub fn min(&self) -> Option<f64> {
+        self.values.iter().copied().reduce(f64::min)
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        self.values.iter().copied().reduce(f64::max)
+    }
+
+    // Population standard deviation
+    pub fn stddev(&self) -> Option<f64> {
+        let mean = self.mean()?;
+        let variance = self.values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / self.values.len() as f64;
+        Some(variance.sqrt())
+    }
+}
+
+impl Bolt for StatsBolt {
     fn execute(&mut self, input: &str) -> Vec<String> {
         if let Ok(num) = input.trim().parse::<f64>() {
-            self.total += num;
-            vec![format!("Sum: {}", self.total)]
+            self.values.push(num);
+            vec![format!("Stats: n={} mean={:.2}", self.count(), self.mean().unwrap_or(0.0))]
         } else {
             vec!["Invalid number".to_string()]
         }
     }
 }
 
+// Flags values that sit more than `threshold` population standard deviations from
+// the mean of the previous `window` values. Each value joins the window after it is
+// scored, so a spike is judged against the history before it
+pub struct ZScoreDetector {
+    window: usize,
+    threshold: f64,
+    values: VecDeque<f64>,
+}
+
+impl ZScoreDetector {
+    pub fn new(window: usize, threshold: f64) -> Self {
+        ZScoreDetector {
+            window: window.max(2),
+            threshold,
+            values: VecDeque::new(),
+        }
+    }
+
+    // The z-score when it exceeds the threshold in either direction. Needs two prior
+    // values; against a perfectly flat window any change scores as infinite
+    pub fn push(&mut self, value: f64) -> Option<f64> {
+        let z = self.z_score(value);
+        if self.values.len() == self.window {
+            self.values.pop_front();
+        }
+        self.values.push_back(value);
+        z.filter(|z| z.abs() > self.threshold)
+    }
+
+    fn z_score(&self, value: f64) -> Option<f64> {
+        if self.values.len() < 2 {
+            return None;
+        }
+        let n = self.values.len() as f64;
+        let mean = self.values.iter().sum::<f64>() / n;
+        let std = (self.values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+        if std == 0.0 {
+            return (value != mean).then(|| (value - mean).signum() * f64::INFINITY);
+        }
+        Some((value - mean) / std)
+    }
+}
+
+// Accumulation Topology: running on-balance volume over "price,volume" ticks
+pub struct AccumulationBolt {
+    obv: f64,
+    last_price: Option<f64>,
+}
+
+impl Default for AccumulationBolt {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AccumulationBolt {
+    pub fn new() -> Self {
+        AccumulationBolt {
+            obv: 0.0,
+            last_price: None,
+        }
+    }
+
+    pub fn obv(&self) -> f64 {
+        self.obv
+    }
+
+    // Volume counts for up-ticks and against down-ticks; the first tick and unchanged
+    // prices only set the reference price. Malformed input leaves the state untouched
+    pub fn try_execute(&mut self, input: &str) -> Result<f64, String> {
+        let (price, volume) = input
+            .trim()
+            .split_once(',')
+            .ok_or_else(|| format!("Invalid tick '{}': expected price,volume", input.trim()))?;
+        let price = price
+            .trim()
+            .parse::<f64>()
+            .map_err(|e| format!("Invalid price '{}': {}", price.trim(), e))?;
+        let volume = volume
+            .trim()
+            .parse::<u64>()
+            .map_err(|e| format!("Invalid volume '{}': {}", volume.trim(), e))? as f64;
+
+        match self.last_price {
+            Some(last) if price > last => self.obv += volume,
+            Some(last) if price < last => self.obv -= volume,
+            _ => {}
+        }
+        self.last_price = Some(price);
+        Ok(self.obv)
+    }
+}
+
+impl Bolt for AccumulationBolt {
+    fn execute(&mut self, input: &str) -> Vec<String> {
+        match self.try_execute(input) {
+            Ok(obv) => vec![format!("OBV: {}", obv)],
+            Err(message) => vec![message],
+        }
+    }
+}
+
 // Edison ⚡ Topology: Electric/Energy processing
 pub struct EdisonBolt {
     voltage: f64,
@@ -155,6 +553,9 @@ impl Bolt for PolymathBolt {
 pub struct KeyBounceBolt {
     last_key: Option<String>,
     bounce_count: usize,
+    // Debounce window for execute_with_clock; a repeat after it elapses is a new press
+    window_ms: u64,
+    last_at: u64,
 }
 
 impl Default for KeyBounceBolt {
@@ -165,47 +566,164 @@ impl Default for KeyBounceBolt {
 
 impl KeyBounceBolt {
     pub fn new() -> Self {
+        Self::with_window(u64::MAX)
+    }
+
+    pub fn with_window(window_ms: u64) -> Self {
         KeyBounceBolt {
             last_key: None,
             bounce_count: 0,
+            window_ms,
+            last_at: 0,
         }
     }
-}
 
-impl Bolt for KeyBounceBolt {
-    fn execute(&mut self, input: &str) -> Vec<String> {
+    // Repeats of the last key within the window are filtered; the window restarts
+    // on every accepted key
+    pub fn execute_with_clock(&mut self, input: &str, clock: &dyn Clock) -> Vec<String> {
+        let now = clock.now_ms();
         if let Some(ref last) = self.last_key {
-            if last == input {
+            if last == input && now.saturating_sub(self.last_at) <= self.window_ms {
                 self.bounce_count += 1;
                 return vec![format!("Bounce filtered: {}", input)];
             }
         }
         self.last_key = Some(input.to_string());
         self.bounce_count = 0;
+        self.last_at = now;
         vec![format!("Key accepted: {}", input)]
     }
 }
 
+impl Bolt for KeyBounceBolt {
+    // Without a clock every repeat of the last key is a bounce
+    fn execute(&mut self, input: &str) -> Vec<String> {
+        self.execute_with_clock(input, &FrozenClock)
+    }
+}
+
+struct FrozenClock;
+
+impl Clock for FrozenClock {
+    fn now_ms(&self) -> u64 {
+        0
+    }
+}
+
+// Rate Limit Topology: passes at most `max` tuples through any rolling window of
+// `window_ms`; the rest are reported as rate limited
+pub struct RateLimitBolt {
+    max: usize,
+    window_ms: u64,
+    accepted: VecDeque<u64>,
+    last_ms: u64,
+}
+
+impl RateLimitBolt {
+    pub fn new(max: usize, window_ms: u64) -> Self {
+        RateLimitBolt {
+            max,
+            window_ms,
+            accepted: VecDeque::new(),
+            last_ms: 0,
+        }
+    }
+
+    // Tuples accepted within the window ending at the latest timestamp seen
+    pub fn in_window(&self) -> usize {
+        self.accepted.len()
+    }
+
+    pub fn execute_at(&mut self, input: &str, t_ms: u64) -> Vec<String> {
+        self.last_ms = self.last_ms.max(t_ms);
+        // A tuple accepted at t stops counting once t + window_ms has passed
+        while self.accepted.front().is_some_and(|&t| t + self.window_ms <= self.last_ms) {
+            self.accepted.pop_front();
+        }
+        if self.accepted.len() >= self.max {
+            return vec![format!("Rate limited: {}", input)];
+        }
+        self.accepted.push_back(self.last_ms);
+        vec![input.to_string()]
+    }
+}
+
+impl Bolt for RateLimitBolt {
+    // Untimed tuples count as arriving at the latest timestamp seen
+    fn execute(&mut self, input: &str) -> Vec<String> {
+        self.execute_at(input, self.last_ms)
+    }
+}
+
+// Compose Topology: dead-key / IME-style combining, e.g. "a" then "'" -> "á"
+pub struct ComposeBolt {
+    table: HashMap<(String, String), String>,
+    pending: Option<String>,
+}
+
+impl ComposeBolt {
+    pub fn new(table: HashMap<(String, String), String>) -> Self {
+        ComposeBolt { table, pending: None }
+    }
+
+    pub fn pending(&self) -> Option<&str> {
+        self.pending.as_deref()
+    }
+
+    // Emit a buffered key that never got a second half
+    pub fn flush(&mut self) -> Vec<String> {
+        self.pending.take().into_iter().collect()
+    }
+}
+
+impl Bolt for ComposeBolt {
+    fn execute(&mut self, input: &str) -> Vec<String> {
+        let Some(first) = self.pending.take() else {
+            self.pending = Some(input.to_string());
+            return Vec::new();
+        };
+        match self.table.get(&(first.clone(), input.to_string())) {
+            Some(combined) => vec![combined.clone()],
+            None => vec![first, input.to_string()],
+        }
+    }
+}
+
 // Randomize Keys 🎹 Topology: Random key generation/processing
+// 88 keys on piano
+pub const PIANO_KEYS: u64 = 88;
+
 pub struct RandomizeKeysBolt {
-    seed: u64,
+    rng: Rng,
+    num_keys: u64,
 }
 
 impl RandomizeKeysBolt {
     pub fn new(seed: u64) -> Self {
-        RandomizeKeysBolt { seed }
+        Self::with_range(seed, PIANO_KEYS)
+    }
+
+    // Map onto any key count, e.g. 61 for a MIDI controller; 0 is clamped to 1
+    pub fn with_range(seed: u64, num_keys: u64) -> Self {
+        RandomizeKeysBolt {
+            rng: Rng::new(seed),
+            num_keys: num_keys.max(1),
+        }
     }
 
-    fn simple_random(&mut self) -> u64 {
-        self.seed = self.seed.wrapping_mul(1664525).wrapping_add(1013904223);
-        self.seed
+    pub fn num_keys(&self) -> u64 {
+        self.num_keys
+    }
+
+    fn next_key(&mut self) -> u64 {
+        self.rng.gen_below(self.num_keys)
     }
 }
 
 impl Bolt for RandomizeKeysBolt {
     fn execute(&mut self, input: &str) -> Vec<String> {
-        let rand = self.simple_random();
-        vec![format!("🎹 {} -> Random: {}", input, rand % 88)] // 88 keys on piano
+        let key = self.next_key();
+        vec![format!("🎹 {} -> Random: {}", input, key)]
     }
 }
 
@@ -217,6 +735,7 @@ pub struct StormTopology {
     pub polymath: PolymathBolt,
     pub key_bounce: KeyBounceBolt,
     pub randomize_keys: RandomizeKeysBolt,
+    registry: HashMap<String, Box<dyn Bolt>>,
 }
 
 impl Default for StormTopology {
@@ -234,9 +753,19 @@ impl StormTopology {
             polymath: PolymathBolt::new(),
             key_bounce: KeyBounceBolt::new(),
             randomize_keys: RandomizeKeysBolt::new(42),
+            registry: HashMap::new(),
         }
     }
 
+    // Add a bolt that can be looked up by name at runtime; re-registering a name replaces it
+    pub fn register(&mut self, name: &str, bolt: Box<dyn Bolt>) {
+        self.registry.insert(name.to_string(), bolt);
+    }
+
+    pub fn execute(&mut self, name: &str, input: &str) -> Option<Vec<String>> {
+        self.registry.get_mut(name).map(|bolt| bolt.execute(input))
+    }
+
     pub fn display(&self) -> String {
         "Storm Topologies:\n  • Word Count\n  • Sum\n  • Edison ⚡\n  • Polymath 🌐\n  • Key Bounce\n  • Randomize Keys 🎹".to_string()
     }
@@ -296,7 +825,7 @@ mod tests {
     fn test_sum_bolt_invalid_input() {
         let mut bolt = SumBolt::new();
         let result = bolt.execute("not a number");
-        assert_eq!(result[0], "Invalid number");
+        assert!(result[0].starts_with("Invalid number"));
     }
 
     #[test]
@@ -372,6 +901,128 @@ mod tests {
         assert!(result[0].contains("accepted"));
     }
 
+    fn accent_table() -> HashMap<(String, String), String> {
+        let mut table = HashMap::new();
+        table.insert(("a".to_string(), "'".to_string()), "á".to_string());
+        table.insert(("e".to_string(), "`".to_string()), "è".to_string());
+        table
+    }
+
+    #[test]
+    fn test_compose_bolt_combines_pair() {
+        let mut bolt = ComposeBolt::new(accent_table());
+        assert!(bolt.execute("a").is_empty());
+        assert_eq!(bolt.pending(), Some("a"));
+        assert_eq!(bolt.execute("'"), vec!["á"]);
+        assert_eq!(bolt.pending(), None);
+    }
+
+    #[test]
+    fn test_compose_bolt_flushes_non_composing_pair() {
+        let mut bolt = ComposeBolt::new(accent_table());
+        bolt.execute("a");
+        assert_eq!(bolt.execute("b"), vec!["a", "b"]);
+        assert_eq!(bolt.pending(), None);
+
+        bolt.execute("e");
+        assert_eq!(bolt.flush(), vec!["e"]);
+        assert!(bolt.flush().is_empty());
+    }
+
+    #[test]
+    fn test_filter_bolt_drops_stop_words() {
+        let mut bolt = FilterBolt::new(&["the", "a"]);
+        assert_eq!(bolt.execute("The quick fox ate a bug"), vec!["quick fox ate bug"]);
+    }
+
+    #[test]
+    fn test_chain_bolts_filter_into_word_count() {
+        let bolts: Vec<Box<dyn Bolt>> = vec![Box::new(FilterBolt::new(&["the"])), Box::new(WordCountBolt::new())];
+        assert_eq!(chain_bolts(bolts, "the quick the fox"), vec!["Processed: quick fox"]);
+    }
+
+    #[test]
+    fn test_chain_bolts_edge_cases() {
+        assert_eq!(chain_bolts(Vec::new(), "input"), vec!["input"]);
+
+        // A bolt that emits nothing ends the pipeline
+        let bolts: Vec<Box<dyn Bolt>> = vec![
+            Box::new(ComposeBolt::new(HashMap::new())),
+            Box::new(WordCountBolt::new()),
+        ];
+        assert!(chain_bolts(bolts, "a").is_empty());
+    }
+
+    #[test]
+    fn test_sum_bolt_try_execute_reports_bad_token() {
+        let mut bolt = SumBolt::new();
+        assert_eq!(bolt.try_execute(" 4.5 "), Ok(4.5));
+
+        let err = bolt.try_execute("12abc").unwrap_err();
+        assert!(err.contains("'12abc'"), "{}", err);
+        assert_eq!(bolt.get_total(), 4.5);
+
+        assert!(bolt.execute("oops")[0].starts_with("Invalid number 'oops'"));
+    }
+
+    #[test]
+    fn test_sum_bolt_snapshot_restore() {
+        let mut bolt = SumBolt::new();
+        bolt.execute("10");
+        let snap = bolt.snapshot();
+
+        bolt.execute("5");
+        assert_eq!(bolt.get_total(), 15.0);
+        bolt.restore(snap);
+        assert_eq!(bolt.get_total(), 10.0);
+
+        bolt.reset();
+        assert_eq!(bolt.get_total(), 0.0);
+    }
+
+    #[test]
+    fn test_word_count_bolt_snapshot_restore() {
+        let mut bolt = WordCountBolt::new();
+        bolt.execute("hello world");
+        let snap = bolt.snapshot();
+
+        bolt.execute("hello again");
+        assert_eq!(bolt.get_counts()["hello"], 2);
+        bolt.restore(snap);
+        assert_eq!(bolt.get_counts()["hello"], 1);
+        assert!(!bolt.get_counts().contains_key("again"));
+
+        bolt.reset();
+        assert!(bolt.get_counts().is_empty());
+    }
+
+    #[test]
+    fn test_stats_bolt_summary() {
+        let mut bolt = StatsBolt::new();
+        for n in [7, 3, 10, 1, 5, 9, 2, 8, 4, 6] {
+            bolt.execute(&n.to_string());
+        }
+        assert_eq!(bolt.execute("abc"), vec!["Invalid number"]);
+
+        assert_eq!(bolt.count(), 10);
+        assert_eq!(bolt.mean(), Some(5.5));
+        assert_eq!(bolt.median(), Some(5.5));
+        assert!((bolt.percentile(90.0).unwrap() - 9.1).abs() < 1e-9);
+        assert_eq!(bolt.percentile(100.0), Some(10.0));
+        assert_eq!(bolt.min(), Some(1.0));
+        assert_eq!(bolt.max(), Some(10.0));
+        assert!((bolt.stddev().unwrap() - 8.25_f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_stats_bolt_empty() {
+        let bolt = StatsBolt::new();
+        assert_eq!(bolt.mean(), None);
+        assert_eq!(bolt.median(), None);
+        assert_eq!(bolt.min(), None);
+        assert_eq!(bolt.stddev(), None);
+    }
+
     #[test]
     fn test_randomize_keys_bolt_execute() {
         let mut bolt = RandomizeKeysBolt::new(42);
@@ -388,6 +1039,21 @@ mod tests {
         assert_eq!(result1[0], result2[0]);
     }
 
+    #[test]
+    fn test_randomize_keys_bolt_configured_range() {
+        let mut bolt = RandomizeKeysBolt::with_range(7, 61);
+        assert_eq!(bolt.num_keys(), 61);
+        for _ in 0..1_000 {
+            assert!(bolt.next_key() < 61);
+        }
+
+        let mut pads = RandomizeKeysBolt::with_range(7, 0);
+        assert_eq!(pads.num_keys(), 1);
+        assert_eq!(pads.execute("hit"), vec!["🎹 hit -> Random: 0".to_string()]);
+
+        assert_eq!(RandomizeKeysBolt::new(7).num_keys(), PIANO_KEYS);
+    }
+
     #[test]
     fn test_storm_topology_new() {
         let topology = StormTopology::new();
@@ -395,6 +1061,31 @@ mod tests {
         assert_eq!(topology.sum.get_total(), 0.0);
     }
 
+    struct EchoBolt {
+        calls: usize,
+    }
+
+    impl Bolt for EchoBolt {
+        fn execute(&mut self, input: &str) -> Vec<String> {
+            self.calls += 1;
+            vec![format!("{} #{}", input, self.calls)]
+        }
+    }
+
+    #[test]
+    fn test_storm_topology_registry_dispatch() {
+        let mut topology = StormTopology::new();
+        topology.register("echo", Box::new(EchoBolt { calls: 0 }));
+        topology.register("sum", Box::new(SumBolt::new()));
+
+        assert_eq!(topology.execute("echo", "hi"), Some(vec!["hi #1".to_string()]));
+        assert_eq!(topology.execute("echo", "hi"), Some(vec!["hi #2".to_string()]));
+        assert_eq!(topology.execute("sum", "2.5"), Some(vec!["Sum: 2.5".to_string()]));
+        assert_eq!(topology.execute("missing", "hi"), None);
+        // Registered bolts are separate from the built-in fields
+        assert_eq!(topology.sum.get_total(), 0.0);
+    }
+
     #[test]
     fn test_storm_topology_display() {
         let topology = StormTopology::new();
@@ -403,4 +1094,156 @@ mod tests {
         assert!(display.contains("Edison"));
         assert!(display.contains("Polymath"));
     }
+
+    #[test]
+    fn test_accumulation_bolt_rising_prices() {
+        let mut bolt = AccumulationBolt::new();
+        assert_eq!(bolt.execute("100.0,500"), vec!["OBV: 0"]);
+        bolt.execute("101.0,1000");
+        bolt.execute("102.5,2000");
+        assert_eq!(bolt.execute("103.0,1500"), vec!["OBV: 4500"]);
+        assert_eq!(bolt.obv(), 4500.0);
+    }
+
+    #[test]
+    fn test_accumulation_bolt_down_ticks_and_bad_input() {
+        let mut bolt = AccumulationBolt::new();
+        bolt.execute("100,1000");
+        bolt.execute("99,300");
+        bolt.execute("99,700");
+        assert_eq!(bolt.obv(), -300.0);
+
+        assert!(bolt.execute("abc,10")[0].starts_with("Invalid price"));
+        assert!(bolt.execute("101,-5")[0].starts_with("Invalid volume"));
+        assert!(bolt.execute("101")[0].starts_with("Invalid tick"));
+        // Skipped ticks don't move the reference price either
+        assert_eq!(bolt.try_execute("100,50"), Ok(-250.0));
+    }
+
+    const TOKENIZER_SENTENCE: &str = "The co-founder's plan: don't stop. 'Go' now";
+
+    #[test]
+    fn test_tokenize_whitespace() {
+        assert_eq!(
+            tokenize(TOKENIZER_SENTENCE, TokenizeMode::Whitespace),
+            vec!["The", "co-founder's", "plan:", "don't", "stop.", "'Go'", "now"]
+        );
+        assert_eq!(WordCountBolt::new().mode(), TokenizeMode::Whitespace);
+    }
+
+    #[test]
+    fn test_tokenize_alphanumeric() {
+        assert_eq!(
+            tokenize(TOKENIZER_SENTENCE, TokenizeMode::Alphanumeric),
+            vec!["The", "co", "founder", "s", "plan", "don", "t", "stop", "Go", "now"]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_alphanumeric_with_apostrophe() {
+        assert_eq!(
+            tokenize(TOKENIZER_SENTENCE, TokenizeMode::AlphanumericWithApostrophe),
+            vec!["The", "co", "founder's", "plan", "don't", "stop", "Go", "now"]
+        );
+
+        let mut bolt = WordCountBolt::with_mode(TokenizeMode::AlphanumericWithApostrophe);
+        bolt.execute("Don't stop. don't!");
+        assert_eq!(bolt.get_counts().get("don't"), Some(&2));
+        assert_eq!(bolt.get_counts().get("stop"), Some(&1));
+    }
+
+    #[test]
+    fn test_rate_limit_bolt_burst_then_recovers() {
+        let mut bolt = RateLimitBolt::new(3, 1_000);
+        let burst: Vec<Vec<String>> = (0..5).map(|i| bolt.execute_at(&format!("t{}", i), 100 + i)).collect();
+        assert_eq!(burst[0], vec!["t0"]);
+        assert_eq!(burst[2], vec!["t2"]);
+        assert_eq!(burst[3], vec!["Rate limited: t3"]);
+        assert_eq!(burst[4], vec!["Rate limited: t4"]);
+        assert_eq!(bolt.in_window(), 3);
+
+        // Still inside the window of the first accepted tuple
+        assert_eq!(bolt.execute_at("late", 1_099), vec!["Rate limited: late"]);
+        // t0 (at 100) has aged out, but t1 and t2 still count
+        assert_eq!(bolt.execute_at("next", 1_100), vec!["next"]);
+        assert_eq!(bolt.execute("untimed"), vec!["Rate limited: untimed"]);
+        assert_eq!(bolt.execute_at("later", 2_200), vec!["later"]);
+        assert_eq!(bolt.in_window(), 1);
+    }
+
+    #[test]
+    fn test_key_bounce_window_with_mock_clock() {
+        use crate::clock::MockClock;
+
+        let mut clock = MockClock::new(0);
+        let mut bolt = KeyBounceBolt::with_window(50);
+        assert_eq!(bolt.execute_with_clock("a", &clock), vec!["Key accepted: a"]);
+        clock.advance(30);
+        assert_eq!(bolt.execute_with_clock("a", &clock), vec!["Bounce filtered: a"]);
+        clock.advance(51);
+        assert_eq!(bolt.execute_with_clock("a", &clock), vec!["Key accepted: a"]);
+    }
+
+    #[test]
+    fn test_bolt_metrics_buckets_and_p99() {
+        let mut metrics = BoltMetrics::default();
+        for _ in 0..99 {
+            metrics.record(500, 1);
+        }
+        metrics.record(2_500_000, 1);
+        assert_eq!(metrics.histogram()[0], 99);
+        assert_eq!(metrics.histogram()[4], 1);
+        // 99 of 100 executes finished under 1µs; the outlier sits above p99
+        assert_eq!(metrics.p99_nanos(), 1_000);
+
+        metrics.record(2_000_000, 0);
+        assert_eq!(metrics.p99_nanos(), 2_500_000);
+        assert_eq!(metrics.executions, 101);
+        assert_eq!(metrics.tuples_emitted, 100);
+        assert_eq!(BoltMetrics::default().p99_nanos(), 0);
+    }
+
+    struct SlowBolt;
+
+    impl Bolt for SlowBolt {
+        fn execute(&mut self, input: &str) -> Vec<String> {
+            std::thread::sleep(std::time::Duration::from_millis(2));
+            vec![input.to_string()]
+        }
+    }
+
+    #[test]
+    fn test_metered_bolt_records_delay() {
+        let mut bolt = MeteredBolt::new(SlowBolt);
+        for i in 0..3 {
+            assert_eq!(bolt.execute(&i.to_string()), vec![i.to_string()]);
+        }
+        let metrics = bolt.metrics();
+        assert_eq!(metrics.executions, 3);
+        // A 2ms sleep can't finish in under 1ms, so nothing lands below that bucket
+        assert_eq!(metrics.histogram()[..4].iter().sum::<u64>(), 0);
+        assert_eq!(metrics.histogram()[4..].iter().sum::<u64>(), 3);
+        assert!(metrics.p99_nanos() >= 2_000_000);
+    }
+
+    #[test]
+    fn test_zscore_detector_flags_only_spike() {
+        let mut detector = ZScoreDetector::new(10, 3.0);
+        let steady = [100.0, 101.0, 99.0, 100.5, 99.5, 100.0, 101.0, 99.0, 100.0, 100.5];
+        for value in steady {
+            assert_eq!(detector.push(value), None);
+        }
+        let z = detector.push(110.0).unwrap();
+        assert!(z > 3.0);
+        assert_eq!(detector.push(100.0), None);
+    }
+
+    #[test]
+    fn test_zscore_detector_flat_window() {
+        let mut detector = ZScoreDetector::new(3, 2.0);
+        assert_eq!(detector.push(5.0), None);
+        assert_eq!(detector.push(5.0), None);
+        assert_eq!(detector.push(5.0), None);
+        assert_eq!(detector.push(4.0), Some(f64::NEG_INFINITY));
+    }
 }
@@ -1,9 +1,58 @@
 // SWIN Transformer
 // Shifted Window Transformer with 16 Heads, Grey Eyes, 600 Shades
 
+use serde::{Deserialize, Serialize};
+use std::fs;
 use std::vec::Vec;
 
-#[derive(Debug, Clone)]
+// Nonlinearity applied by the feed-forward sublayer
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Activation {
+    Relu,
+    Gelu,
+    Identity,
+}
+
+impl Activation {
+    pub fn apply(&self, x: f64) -> f64 {
+        match self {
+            Activation::Relu => relu(x),
+            Activation::Gelu => gelu(x),
+            Activation::Identity => x,
+        }
+    }
+}
+
+pub fn relu(x: f64) -> f64 {
+    x.max(0.0)
+}
+
+pub fn gelu(x: f64) -> f64 {
+    // Tanh approximation of x * Φ(x)
+    let inner = (2.0 / std::f64::consts::PI).sqrt() * (x + 0.044715 * x.powi(3));
+    0.5 * x * (1.0 + inner.tanh())
+}
+
+// Numerically stable softmax; shifts by the max score before exponentiating
+pub fn softmax(scores: &[f64]) -> Vec<f64> {
+    let max = scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let exps: Vec<f64> = scores.iter().map(|&s| (s - max).exp()).collect();
+    let total: f64 = exps.iter().sum();
+    exps.iter().map(|e| e / total).collect()
+}
+
+// Smallest temperature accepted; anything lower is effectively argmax anyway
+pub const MIN_TEMPERATURE: f64 = 1e-6;
+
+// Range gamma is clamped to before shading
+pub const MIN_GAMMA: f64 = 0.1;
+pub const MAX_GAMMA: f64 = 10.0;
+
+fn default_temperature() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AttentionHead {
     pub head_id: usize,
     pub dim: usize,
@@ -20,14 +69,32 @@ impl AttentionHead {
     }
 
     pub fn forward(&self, input: &[f64]) -> Vec<f64> {
-        // Simplified attention mechanism
-        input.iter().map(|&x| x * 0.9).collect()
+        // Simplified attention mechanism with per-position learned bias
+        input
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| {
+                let bias = if self.weights.is_empty() {
+                    0.0
+                } else {
+                    self.weights[i % self.weights.len()]
+                };
+                x * 0.9 + bias
+            })
+            .collect()
     }
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct SwinTransformer {
     pub heads: Vec<AttentionHead>,
     pub grey_shades: usize,
+    pub activation: Activation,
+    // Softmax temperature for attention_weights only; forward_pass and
+    // forward_pass_parallel don't use it. Older saved models predate this field and
+    // load with the standard scaling
+    #[serde(default = "default_temperature")]
+    pub temperature: f64,
 }
 
 impl SwinTransformer {
@@ -39,6 +106,8 @@ impl SwinTransformer {
         SwinTransformer {
             heads,
             grey_shades,
+            activation: Activation::Gelu,
+            temperature: default_temperature(),
         }
     }
 
@@ -46,23 +115,84 @@ impl SwinTransformer {
         Self::new(16, 600)
     }
 
+    pub fn with_activation(mut self, activation: Activation) -> Self {
+        self.activation = activation;
+        self
+    }
+
+    // Lower temperature sharpens attention_weights, higher flattens it; 1.0 is standard
+    // scaling. Forward pass outputs are unaffected
+    pub fn with_temperature(mut self, temperature: f64) -> Self {
+        self.temperature = temperature.max(MIN_TEMPERATURE);
+        self
+    }
+
+    // Scaled dot-product weights: scores / (sqrt(dim) * temperature), then softmax
+    pub fn attention_weights(&self, scores: &[f64], dim: usize) -> Vec<f64> {
+        let scale = (dim.max(1) as f64).sqrt() * self.temperature;
+        let scaled: Vec<f64> = scores.iter().map(|s| s / scale).collect();
+        softmax(&scaled)
+    }
+
     pub fn forward_pass(&self, input: &[f64]) -> Vec<f64> {
-        // Multi-head attention
-        let mut outputs = Vec::new();
+        // Multi-head attention followed by the feed-forward sublayer
+        let mut outputs = Vec::with_capacity(input.len() * self.heads.len());
         for head in &self.heads {
-            outputs.extend(head.forward(input));
+            outputs.extend(self.feed_forward(&head.forward(input)));
         }
         outputs
     }
 
-    pub fn grey_eyes_processing(&self, image: &[u8]) -> Vec<u8> {
-        // Convert to greyscale with 600 shades
-        let max_shade = self.grey_shades as f64;
+    // Same result as forward_pass with each head on its own scoped thread; outputs are
+    // joined in head order so the concatenation is identical
+    pub fn forward_pass_parallel(&self, input: &[f64]) -> Vec<f64> {
+        let head_outputs: Vec<Vec<f64>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .heads
+                .iter()
+                .map(|head| scope.spawn(move || self.feed_forward(&head.forward(input))))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("attention head thread panicked"))
+                .collect()
+        });
+        head_outputs.concat()
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let json = serde_json::to_string(self)
+            .map_err(|e| format!("Failed to serialize transformer: {}", e))?;
+        fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path, e))
+    }
+
+    pub fn load(path: &str) -> Result<SwinTransformer, String> {
+        let json = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        serde_json::from_str(&json).map_err(|e| format!("Failed to parse transformer: {}", e))
+    }
+
+    pub fn forward_batch(&self, inputs: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        inputs.iter().map(|input| self.forward_pass(input)).collect()
+    }
+
+    pub fn feed_forward(&self, input: &[f64]) -> Vec<f64> {
+        input.iter().map(|&x| self.activation.apply(x)).collect()
+    }
+
+    pub fn grey_eyes_processing(&self, image: &[u8]) -> Vec<u16> {
+        // Convert to greyscale, scaling 0..=255 linearly onto 0..=grey_shades
+        self.grey_eyes_gamma(image, 1.0)
+    }
+
+    // Perceptual variant: (pixel / 255)^gamma * shades; gamma above 1 darkens mid-tones
+    pub fn grey_eyes_gamma(&self, image: &[u8], gamma: f64) -> Vec<u16> {
+        let gamma = gamma.clamp(MIN_GAMMA, MAX_GAMMA);
+        let max_shade = self.grey_shades.min(u16::MAX as usize) as f64;
         image
             .iter()
             .map(|&pixel| {
                 let normalized = pixel as f64 / 255.0;
-                (normalized * max_shade) as u8
+                (normalized.powf(gamma) * max_shade).round() as u16
             })
             .collect()
     }
@@ -77,6 +207,27 @@ impl SwinTransformer {
             .collect()
     }
 
+    // Shade each `window`-sized slice, stepping `stride` values at a time. A stride
+    // wider than the window skips the values in between; the last window may be
+    // short, and sliding stops once a window reaches the end of the data
+    pub fn process_windows(&self, data: &[f64], window: usize, stride: usize) -> Vec<Vec<usize>> {
+        if window == 0 {
+            return Vec::new();
+        }
+        let stride = stride.max(1);
+        let mut windows = Vec::new();
+        let mut start = 0;
+        while start < data.len() {
+            let end = (start + window).min(data.len());
+            windows.push(self.process_with_600_shades(&data[start..end]));
+            if end == data.len() {
+                break;
+            }
+            start += stride;
+        }
+        windows
+    }
+
     pub fn display(&self) -> String {
         format!(
             "SWIN Transformer:\n  • {} Attention Heads\n  • Grey Eyes Processing\n  • {} Shades\n  • Forward Pass Enabled",
@@ -166,6 +317,80 @@ mod tests {
         assert_eq!(processed.len(), 2);
     }
 
+    #[test]
+    fn test_relu_zeroes_negatives() {
+        assert_eq!(relu(-2.5), 0.0);
+        assert_eq!(relu(0.0), 0.0);
+        assert_eq!(relu(3.0), 3.0);
+    }
+
+    #[test]
+    fn test_gelu_reference_values() {
+        assert!(gelu(0.0).abs() < 1e-9);
+        assert!((gelu(1.0) - 0.8413).abs() < 1e-3);
+        assert!((gelu(-1.0) + 0.1587).abs() < 1e-3);
+        assert!((gelu(2.0) - 1.9545).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_forward_pass_applies_activation() {
+        let swin = SwinTransformer::new(2, 600).with_activation(Activation::Relu);
+        let output = swin.forward_pass(&[-1.0, 2.0]);
+        assert_eq!(output, vec![0.0, 1.8, 0.0, 1.8]);
+
+        let linear = SwinTransformer::new(1, 600).with_activation(Activation::Identity);
+        assert_eq!(linear.forward_pass(&[-1.0]), vec![-0.9]);
+    }
+
+    #[test]
+    fn test_save_load_round_trip() {
+        let mut swin = SwinTransformer::new(4, 300);
+        for head in &mut swin.heads {
+            for (i, w) in head.weights.iter_mut().enumerate() {
+                *w = ((head.head_id * 64 + i) as f64 * 0.37).sin();
+            }
+        }
+
+        let path = std::env::temp_dir().join("swin_transformer_round_trip.json");
+        let path = path.to_str().unwrap();
+        swin.save(path).unwrap();
+        let loaded = SwinTransformer::load(path).unwrap();
+        let _ = fs::remove_file(path);
+
+        assert_eq!(loaded.heads.len(), 4);
+        assert_eq!(loaded.grey_shades, 300);
+        let input = vec![0.5, -1.25, 3.0, 7.5];
+        let expected: Vec<u64> = swin.forward_pass(&input).iter().map(|x| x.to_bits()).collect();
+        let actual: Vec<u64> = loaded.forward_pass(&input).iter().map(|x| x.to_bits()).collect();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        assert!(SwinTransformer::load("/nonexistent/swin.json").is_err());
+    }
+
+    #[test]
+    fn test_grey_eyes_full_shade_range() {
+        let swin = SwinTransformer::with_16_heads();
+        let processed = swin.grey_eyes_processing(&[0, 128, 255]);
+        assert_eq!(processed[0], 0);
+        assert_eq!(processed[1], 301);
+        assert_eq!(processed[2], 600);
+    }
+
+    #[test]
+    fn test_forward_batch() {
+        let swin = SwinTransformer::with_16_heads();
+        let inputs = vec![vec![1.0, 2.0], vec![], vec![0.5, -0.5, 3.0, 4.0]];
+        let outputs = swin.forward_batch(&inputs);
+        assert_eq!(outputs.len(), 3);
+        for (input, output) in inputs.iter().zip(&outputs) {
+            assert_eq!(output.len(), 16 * input.len());
+            assert_eq!(output, &swin.forward_pass(input));
+        }
+    }
+
     #[test]
     fn test_display() {
         let swin = SwinTransformer::with_16_heads();
@@ -173,4 +398,99 @@ mod tests {
         assert!(display.contains("16 Attention Heads"));
         assert!(display.contains("600 Shades"));
     }
+
+    #[test]
+    fn test_softmax_sums_to_one() {
+        let weights = softmax(&[1.0, 2.0, 3.0]);
+        assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(weights[2] > weights[1] && weights[1] > weights[0]);
+        // Large scores don't overflow thanks to the max shift
+        assert!(softmax(&[1000.0, 1000.0]).iter().all(|w| (w - 0.5).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_attention_temperature() {
+        let scores = [1.0, 2.0, 3.0, 4.0];
+        let dim = 4;
+
+        let standard = SwinTransformer::new(1, 600);
+        assert_eq!(standard.temperature, 1.0);
+        let expected = softmax(&[0.5, 1.0, 1.5, 2.0]);
+        assert_eq!(standard.attention_weights(&scores, dim), expected);
+
+        let sharp = SwinTransformer::new(1, 600).with_temperature(0.01);
+        let weights = sharp.attention_weights(&scores, dim);
+        assert!(weights[3] > 0.999);
+
+        let flat = SwinTransformer::new(1, 600).with_temperature(1_000.0);
+        let weights = flat.attention_weights(&scores, dim);
+        assert!(weights.iter().all(|w| (w - 0.25).abs() < 1e-3));
+
+        assert_eq!(SwinTransformer::new(1, 600).with_temperature(0.0).temperature, MIN_TEMPERATURE);
+    }
+
+    #[test]
+    fn test_load_defaults_missing_temperature() {
+        let json = r#"{"heads":[],"grey_shades":600,"activation":"Relu"}"#;
+        let swin: SwinTransformer = serde_json::from_str(json).unwrap();
+        assert_eq!(swin.temperature, 1.0);
+    }
+
+    #[test]
+    fn test_grey_eyes_gamma() {
+        let swin = SwinTransformer::with_16_heads();
+        let image = vec![0, 128, 255];
+
+        let linear = swin.grey_eyes_gamma(&image, 1.0);
+        assert_eq!(linear, swin.grey_eyes_processing(&image));
+        assert_eq!(linear[1], 301);
+
+        // (128/255)^2.2 * 600 ≈ 132: mid-tones darken, endpoints stay put
+        let corrected = swin.grey_eyes_gamma(&image, 2.2);
+        assert_eq!(corrected[1], 132);
+        assert_eq!(corrected[0], 0);
+        assert_eq!(corrected[2], 600);
+
+        assert_eq!(swin.grey_eyes_gamma(&image, -3.0), swin.grey_eyes_gamma(&image, MIN_GAMMA));
+    }
+
+    #[test]
+    fn test_forward_pass_parallel_matches_sequential() {
+        let mut swin = SwinTransformer::with_16_heads();
+        for head in &mut swin.heads {
+            head.weights = (0..head.dim).map(|i| (i as f64 * 0.01) - head.head_id as f64 * 0.1).collect();
+        }
+        let input: Vec<f64> = (0..500).map(|i| (i as f64 * 0.37).sin()).collect();
+
+        let sequential = swin.forward_pass(&input);
+        let parallel = swin.forward_pass_parallel(&input);
+        assert_eq!(parallel.len(), 500 * 16);
+        assert!(sequential.iter().zip(&parallel).all(|(a, b)| a.to_bits() == b.to_bits()));
+    }
+
+    #[test]
+    fn test_process_windows_overlap() {
+        let swin = SwinTransformer::with_16_heads();
+        let data = [0.0, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6];
+        let windows = swin.process_windows(&data, 3, 2);
+        assert_eq!(windows.len(), 3);
+        assert_eq!(windows[0], swin.process_with_600_shades(&data[0..3]));
+        assert_eq!(windows[1], swin.process_with_600_shades(&data[2..5]));
+        assert_eq!(windows[2], swin.process_with_600_shades(&data[4..7]));
+    }
+
+    #[test]
+    fn test_process_windows_gaps_and_partial() {
+        let swin = SwinTransformer::with_16_heads();
+        let data = [0.0, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6];
+        // Stride 4 leaves gaps: indices 2, 3 and 6 are never shaded
+        let windows = swin.process_windows(&data, 2, 4);
+        assert_eq!(windows.len(), 2);
+        assert_eq!(windows[1], swin.process_with_600_shades(&data[4..6]));
+
+        let partial = swin.process_windows(&data, 3, 3);
+        assert_eq!(partial.iter().map(Vec::len).collect::<Vec<_>>(), vec![3, 3, 1]);
+        assert!(swin.process_windows(&data, 0, 1).is_empty());
+        assert!(swin.process_windows(&[], 3, 1).is_empty());
+    }
 }
@@ -1,107 +1,347 @@
 // Trading Execution
 // Order management and execution
 
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashSet;
+
+use crate::error::BetError;
 use crate::trading_models::Position;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum OrderType {
     Market,
     Limit(f64),
     Stop(f64),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum OrderSide {
     Buy,
     Sell,
 }
 
+impl OrderSide {
+    pub fn flip(&self) -> OrderSide {
+        match self {
+            OrderSide::Buy => OrderSide::Sell,
+            OrderSide::Sell => OrderSide::Buy,
+        }
+    }
+
+    // Quantity as a position delta: positive for buys, negative for sells
+    pub fn signed(&self, qty: f64) -> f64 {
+        match self {
+            OrderSide::Buy => qty,
+            OrderSide::Sell => -qty,
+        }
+    }
+}
+
+// Monotonic id source; a fixed start makes ids reproducible across replays
+#[derive(Debug, Clone)]
+pub struct IdGenerator {
+    next: u64,
+}
+
+impl Default for IdGenerator {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl IdGenerator {
+    pub fn new(start: u64) -> Self {
+        IdGenerator { next: start }
+    }
+
+    pub fn next_id(&mut self) -> u64 {
+        let id = self.next;
+        self.next += 1;
+        id
+    }
+
+    // The id the next call to next_id will return
+    pub fn peek(&self) -> u64 {
+        self.next
+    }
+
+    // Skip past an id issued elsewhere (e.g. replayed from a log) so it isn't reused
+    pub fn observe(&mut self, id: u64) {
+        self.next = self.next.max(id + 1);
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Order {
+    // 0 until stamped from an IdGenerator
+    pub id: u64,
     pub symbol: String,
     pub side: OrderSide,
     pub order_type: OrderType,
     pub quantity: f64,
     pub filled: bool,
+    pub cancelled: bool,
+    // Last timestamp the order may rest in a book; None is good-till-cancelled
+    pub good_till: Option<u64>,
+    // Id of the signal that triggered the order, carried into its trade
+    pub signal_id: Option<u64>,
 }
 
 impl Order {
     pub fn new(symbol: &str, side: OrderSide, order_type: OrderType, quantity: f64) -> Self {
         Order {
+            id: 0,
             symbol: symbol.to_string(),
             side,
             order_type,
             quantity,
             filled: false,
+            cancelled: false,
+            good_till: None,
+            signal_id: None,
         }
     }
 
+    pub fn with_id(mut self, id: u64) -> Self {
+        self.id = id;
+        self
+    }
+
+    pub fn with_signal_id(mut self, signal_id: u64) -> Self {
+        self.signal_id = Some(signal_id);
+        self
+    }
+
+    pub fn with_good_till(mut self, good_till: u64) -> Self {
+        self.good_till = Some(good_till);
+        self
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.good_till.is_some_and(|good_till| now > good_till)
+    }
+
+    pub fn cancel(&mut self) {
+        if !self.filled {
+            self.cancelled = true;
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        !self.filled && !self.cancelled
+    }
+
     pub fn execute(&mut self, price: f64) -> Option<Position> {
-        if self.filled {
+        if !self.is_open() {
             return None;
         }
 
-        match &self.order_type {
-            OrderType::Market => {
-                self.filled = true;
-                Some(Position {
-                    symbol: self.symbol.clone(),
-                    quantity: match self.side {
-                        OrderSide::Buy => self.quantity,
-                        OrderSide::Sell => -self.quantity,
-                    },
-                    avg_price: price,
-                    current_price: price,
-                })
-            }
-            OrderType::Limit(limit_price) => {
-                let can_execute = match self.side {
-                    OrderSide::Buy => price <= *limit_price,
-                    OrderSide::Sell => price >= *limit_price,
-                };
-                if can_execute {
-                    self.filled = true;
-                    Some(Position {
-                        symbol: self.symbol.clone(),
-                        quantity: match self.side {
-                            OrderSide::Buy => self.quantity,
-                            OrderSide::Sell => -self.quantity,
-                        },
-                        avg_price: price,
-                        current_price: price,
-                    })
-                } else {
-                    None
-                }
-            }
-            OrderType::Stop(stop_price) => {
-                let triggered = match self.side {
-                    OrderSide::Buy => price >= *stop_price,
-                    OrderSide::Sell => price <= *stop_price,
-                };
-                if triggered {
-                    self.filled = true;
-                    Some(Position {
-                        symbol: self.symbol.clone(),
-                        quantity: match self.side {
-                            OrderSide::Buy => self.quantity,
-                            OrderSide::Sell => -self.quantity,
-                        },
-                        avg_price: price,
-                        current_price: price,
-                    })
-                } else {
-                    None
-                }
-            }
+        let fills = match &self.order_type {
+            OrderType::Market => true,
+            OrderType::Limit(limit_price) => match self.side {
+                OrderSide::Buy => price <= *limit_price,
+                OrderSide::Sell => price >= *limit_price,
+            },
+            OrderType::Stop(stop_price) => match self.side {
+                OrderSide::Buy => price >= *stop_price,
+                OrderSide::Sell => price <= *stop_price,
+            },
+        };
+        if !fills {
+            return None;
+        }
+
+        self.filled = true;
+        Some(Position {
+            symbol: self.symbol.clone(),
+            quantity: self.side.signed(self.quantity),
+            avg_price: price,
+            current_price: price,
+        })
+    }
+}
+
+// Record of a single fill event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Trade {
+    pub id: u64,
+    pub symbol: String,
+    pub side: OrderSide,
+    pub quantity: f64,
+    pub price: f64,
+    pub timestamp: u64,
+    #[serde(default)]
+    pub signal_id: Option<u64>,
+}
+
+impl Trade {
+    pub fn notional(&self) -> f64 {
+        self.quantity * self.price
+    }
+}
+
+// Append-only log of fills for audit. Trade ids are unique, so replaying the
+// same fills twice is harmless
+#[derive(Debug, Default)]
+pub struct Blotter {
+    trades: Vec<Trade>,
+    ids: IdGenerator,
+    seen: HashSet<u64>,
+}
+
+impl Blotter {
+    pub fn new() -> Self {
+        Blotter {
+            trades: Vec::new(),
+            ids: IdGenerator::default(),
+            seen: HashSet::new(),
+        }
+    }
+
+    pub fn next_id(&self) -> u64 {
+        self.ids.peek()
+    }
+
+    pub fn record(&mut self, trade: Trade) -> Result<(), BetError> {
+        if !self.seen.insert(trade.id) {
+            return Err(BetError::DuplicateTrade(trade.id));
+        }
+        self.ids.observe(trade.id);
+        self.trades.push(trade);
+        Ok(())
+    }
+
+    pub fn trades(&self) -> &[Trade] {
+        &self.trades
+    }
+
+    pub fn for_symbol(&self, symbol: &str) -> Vec<&Trade> {
+        self.trades.iter().filter(|t| t.symbol == symbol).collect()
+    }
+
+    pub fn trades_for_signal(&self, signal_id: u64) -> Vec<&Trade> {
+        self.trades.iter().filter(|t| t.signal_id == Some(signal_id)).collect()
+    }
+
+    pub fn total_volume(&self) -> f64 {
+        self.trades.iter().map(|t| t.quantity).sum()
+    }
+}
+
+// Resting orders waiting for a price that lets them fill
+#[derive(Debug, Default)]
+pub struct OrderBook {
+    resting: Vec<Order>,
+}
+
+impl OrderBook {
+    pub fn new() -> Self {
+        OrderBook { resting: Vec::new() }
+    }
+
+    pub fn add(&mut self, order: Order) {
+        self.resting.push(order);
+    }
+
+    pub fn resting(&self) -> &[Order] {
+        &self.resting
+    }
+
+    // Drop orders whose good-till time has passed, returning them
+    pub fn purge_expired(&mut self, now: u64) -> Vec<Order> {
+        let (expired, live) = std::mem::take(&mut self.resting)
+            .into_iter()
+            .partition(|order| order.is_expired(now));
+        self.resting = live;
+        expired
+    }
+
+    // One matching pass at `price`: purge expired orders first, then fill what
+    // can fill; filled and cancelled orders leave the book
+    pub fn match_orders(&mut self, price: f64, now: u64) -> Vec<Position> {
+        self.purge_expired(now);
+        let fills = self
+            .resting
+            .iter_mut()
+            .filter_map(|order| order.execute(price))
+            .collect();
+        self.resting.retain(|order| order.is_open());
+        fills
+    }
+}
+
+// Entry with attached take-profit and stop-loss exits; the exits only go live
+// once the entry fills, and filling one exit cancels the other (OCO)
+#[derive(Debug, Clone)]
+pub struct BracketOrder {
+    pub entry: Order,
+    pub take_profit: Order,
+    pub stop_loss: Order,
+}
+
+impl BracketOrder {
+    pub fn new(entry: Order, take_profit: Order, stop_loss: Order) -> Self {
+        BracketOrder {
+            entry,
+            take_profit,
+            stop_loss,
         }
     }
+
+    pub fn is_complete(&self) -> bool {
+        self.entry.filled && !self.take_profit.is_open() && !self.stop_loss.is_open()
+    }
+
+    // Feed the next market price; any fills it causes are recorded in `blotter`,
+    // which issues their ids, and returned
+    pub fn on_fill(&mut self, price: f64, timestamp: u64, blotter: &mut Blotter) -> Result<Vec<Trade>, BetError> {
+        let filled = if !self.entry.filled {
+            self.entry.execute(price).map(|_| (self.entry.side, self.entry.quantity))
+        } else if self.take_profit.execute(price).is_some() {
+            self.stop_loss.cancel();
+            Some((self.take_profit.side, self.take_profit.quantity))
+        } else if self.stop_loss.execute(price).is_some() {
+            self.take_profit.cancel();
+            Some((self.stop_loss.side, self.stop_loss.quantity))
+        } else {
+            None
+        };
+
+        let Some((side, quantity)) = filled else {
+            return Ok(Vec::new());
+        };
+        let trade = Trade {
+            id: blotter.next_id(),
+            symbol: self.entry.symbol.clone(),
+            side,
+            quantity,
+            price,
+            timestamp,
+            signal_id: self.entry.signal_id,
+        };
+        blotter.record(trade.clone())?;
+        Ok(vec![trade])
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_order_side_flip_round_trips() {
+        assert_eq!(OrderSide::Buy.flip(), OrderSide::Sell);
+        assert_eq!(OrderSide::Sell.flip(), OrderSide::Buy);
+        assert_eq!(OrderSide::Buy.flip().flip(), OrderSide::Buy);
+    }
+
+    #[test]
+    fn test_order_side_signed() {
+        assert_eq!(OrderSide::Buy.signed(25.0), 25.0);
+        assert_eq!(OrderSide::Sell.signed(25.0), -25.0);
+    }
+
     #[test]
     fn test_order_new() {
         let order = Order::new("TEST", OrderSide::Buy, OrderType::Market, 100.0);
@@ -201,4 +441,174 @@ mod tests {
         let position = order.execute(50.0);
         assert!(position.is_none());
     }
+
+    fn trade(id: u64, symbol: &str, side: OrderSide, quantity: f64) -> Trade {
+        Trade {
+            id,
+            symbol: symbol.to_string(),
+            side,
+            quantity,
+            price: 10.0,
+            timestamp: id,
+            signal_id: None,
+        }
+    }
+
+    #[test]
+    fn test_blotter_record() {
+        let mut blotter = Blotter::new();
+        assert_eq!(blotter.next_id(), 1);
+        blotter.record(trade(1, "AAA", OrderSide::Buy, 100.0)).unwrap();
+        blotter.record(trade(2, "BBB", OrderSide::Sell, 50.0)).unwrap();
+        assert_eq!(blotter.trades().len(), 2);
+        assert_eq!(blotter.next_id(), 3);
+        assert_eq!(blotter.total_volume(), 150.0);
+        assert_eq!(blotter.trades()[1].notional(), 500.0);
+    }
+
+    #[test]
+    fn test_blotter_for_symbol() {
+        let mut blotter = Blotter::new();
+        blotter.record(trade(1, "AAA", OrderSide::Buy, 100.0)).unwrap();
+        blotter.record(trade(2, "BBB", OrderSide::Buy, 50.0)).unwrap();
+        blotter.record(trade(3, "AAA", OrderSide::Sell, 40.0)).unwrap();
+        let aaa = blotter.for_symbol("AAA");
+        assert_eq!(aaa.len(), 2);
+        assert_eq!(aaa[1].side, OrderSide::Sell);
+        assert!(blotter.for_symbol("CCC").is_empty());
+    }
+
+    fn long_bracket() -> BracketOrder {
+        BracketOrder::new(
+            Order::new("TEST", OrderSide::Buy, OrderType::Limit(100.0), 10.0),
+            Order::new("TEST", OrderSide::Sell, OrderType::Limit(110.0), 10.0),
+            Order::new("TEST", OrderSide::Sell, OrderType::Stop(95.0), 10.0),
+        )
+    }
+
+    #[test]
+    fn test_order_is_expired() {
+        let order = Order::new("TEST", OrderSide::Buy, OrderType::Market, 1.0).with_good_till(10);
+        assert!(!order.is_expired(9));
+        assert!(!order.is_expired(10));
+        assert!(order.is_expired(11));
+        assert!(!Order::new("TEST", OrderSide::Buy, OrderType::Market, 1.0).is_expired(u64::MAX));
+    }
+
+    #[test]
+    fn test_order_book_purges_expired_before_matching() {
+        let mut book = OrderBook::new();
+        book.add(Order::new("OLD", OrderSide::Buy, OrderType::Limit(100.0), 5.0).with_good_till(5));
+        book.add(Order::new("NEW", OrderSide::Buy, OrderType::Limit(100.0), 5.0).with_good_till(20));
+
+        // Price would fill both, but OLD expired at t=5
+        let fills = book.match_orders(99.0, 10);
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].symbol, "NEW");
+        assert!(book.resting().is_empty());
+    }
+
+    #[test]
+    fn test_order_book_unexpired_order_survives_pass() {
+        let mut book = OrderBook::new();
+        book.add(Order::new("TEST", OrderSide::Buy, OrderType::Limit(100.0), 5.0).with_good_till(20));
+
+        assert!(book.match_orders(105.0, 10).is_empty());
+        assert_eq!(book.resting().len(), 1);
+
+        assert_eq!(book.match_orders(99.0, 20).len(), 1);
+        assert!(book.resting().is_empty());
+    }
+
+    #[test]
+    fn test_order_cancel() {
+        let mut order = Order::new("TEST", OrderSide::Buy, OrderType::Market, 1.0);
+        order.cancel();
+        assert!(!order.is_open());
+        assert!(order.execute(10.0).is_none());
+    }
+
+    #[test]
+    fn test_bracket_exits_wait_for_entry() {
+        let mut bracket = long_bracket();
+        let mut blotter = Blotter::new();
+        // Price above the entry limit: nothing fills, not even the take-profit
+        assert!(bracket.on_fill(112.0, 1, &mut blotter).unwrap().is_empty());
+        assert!(bracket.take_profit.is_open());
+        let trades = bracket.on_fill(99.0, 2, &mut blotter).unwrap();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].side, OrderSide::Buy);
+        assert_eq!(trades[0].timestamp, 2);
+        assert_eq!(blotter.trades(), &trades[..]);
+    }
+
+    #[test]
+    fn test_bracket_take_profit_cancels_stop() {
+        let mut bracket = long_bracket();
+        let mut blotter = Blotter::new();
+        bracket.on_fill(100.0, 1, &mut blotter).unwrap();
+        assert!(bracket.on_fill(105.0, 2, &mut blotter).unwrap().is_empty());
+        let trades = bracket.on_fill(111.0, 3, &mut blotter).unwrap();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].price, 111.0);
+        assert_eq!(trades[0].id, 2);
+        assert!(bracket.take_profit.filled);
+        assert!(bracket.stop_loss.cancelled);
+        assert!(bracket.is_complete());
+        assert!(bracket.on_fill(90.0, 4, &mut blotter).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_bracket_stop_loss_cancels_take_profit() {
+        let mut bracket = long_bracket();
+        let mut blotter = Blotter::new();
+        bracket.on_fill(100.0, 1, &mut blotter).unwrap();
+        let trades = bracket.on_fill(94.0, 2, &mut blotter).unwrap();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].side, OrderSide::Sell);
+        assert!(bracket.stop_loss.filled);
+        assert!(bracket.take_profit.cancelled);
+        assert!(bracket.on_fill(120.0, 3, &mut blotter).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_brackets_sharing_a_blotter_get_distinct_ids() {
+        let mut blotter = Blotter::new();
+        let mut first = long_bracket();
+        let mut second = long_bracket();
+        first.on_fill(100.0, 1, &mut blotter).unwrap();
+        second.on_fill(100.0, 1, &mut blotter).unwrap();
+        first.on_fill(111.0, 2, &mut blotter).unwrap();
+        second.on_fill(94.0, 2, &mut blotter).unwrap();
+
+        let ids: Vec<u64> = blotter.trades().iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_id_generator_strictly_increasing() {
+        let mut ids = IdGenerator::new(1000);
+        let issued: Vec<u64> = (0..5).map(|_| ids.next_id()).collect();
+        assert_eq!(issued, vec![1000, 1001, 1002, 1003, 1004]);
+        assert!(issued.windows(2).all(|w| w[0] < w[1]));
+
+        ids.observe(2000);
+        assert_eq!(ids.next_id(), 2001);
+        ids.observe(5);
+        assert_eq!(ids.next_id(), 2002);
+        assert_eq!(IdGenerator::default().peek(), 1);
+    }
+
+    #[test]
+    fn test_blotter_rejects_duplicate_trade_id() {
+        let mut blotter = Blotter::new();
+        blotter.record(trade(7, "AAA", OrderSide::Buy, 100.0)).unwrap();
+        assert_eq!(
+            blotter.record(trade(7, "AAA", OrderSide::Buy, 100.0)),
+            Err(BetError::DuplicateTrade(7))
+        );
+        assert_eq!(blotter.trades().len(), 1);
+        // Replayed ids advance the generator past themselves
+        assert_eq!(blotter.next_id(), 8);
+    }
 }
@@ -1,7 +1,8 @@
 // Trading DAG
 // DAG-based trading workflow orchestration
 
-use crate::adag::{OctoTree, Task};
+use crate::adag::{OctoTree, Task, TaskSchedule};
+use crate::error::BetError;
 
 pub struct TradingWorkflow {
     dag: OctoTree,
@@ -18,53 +19,90 @@ impl TradingWorkflow {
         let mut dag = OctoTree::new();
 
         // Build trading workflow DAG
-        dag.add_task(Task {
-            id: "fetch_data".to_string(),
-            name: "Fetch Market Data".to_string(),
-            duration: 2,
-            dependencies: vec![],
-        });
-
-        dag.add_task(Task {
-            id: "calculate_indicators".to_string(),
-            name: "Calculate Technical Indicators".to_string(),
-            duration: 3,
-            dependencies: vec!["fetch_data".to_string()],
-        });
-
-        dag.add_task(Task {
-            id: "generate_signals".to_string(),
-            name: "Generate Trading Signals".to_string(),
-            duration: 2,
-            dependencies: vec!["calculate_indicators".to_string()],
-        });
-
-        dag.add_task(Task {
-            id: "risk_check".to_string(),
-            name: "Risk Management Check".to_string(),
-            duration: 1,
-            dependencies: vec!["generate_signals".to_string()],
-        });
-
-        dag.add_task(Task {
-            id: "execute_trades".to_string(),
-            name: "Execute Trades".to_string(),
-            duration: 2,
-            dependencies: vec!["risk_check".to_string()],
-        });
+        dag.add_task(Task::new("fetch_data", "Fetch Market Data", 2, &[]));
+        dag.add_task(Task::new("calculate_indicators", "Calculate Technical Indicators", 3, &["fetch_data"]));
+        dag.add_task(Task::new("generate_signals", "Generate Trading Signals", 2, &["calculate_indicators"]));
+        dag.add_task(Task::new("risk_check", "Risk Management Check", 1, &["generate_signals"]));
+        dag.add_task(Task::new("execute_trades", "Execute Trades", 2, &["risk_check"]));
 
         TradingWorkflow { dag }
     }
 
-    pub fn get_execution_order(&self) -> Result<Vec<String>, String> {
+    pub fn builder() -> TradingWorkflowBuilder {
+        TradingWorkflowBuilder::new()
+    }
+
+    pub fn get_execution_order(&self) -> Result<Vec<String>, BetError> {
         self.dag.topological_sort()
     }
 
+    // Every task, cloned, in execution order
+    pub fn steps(&self) -> Result<Vec<Task>, BetError> {
+        Ok(self
+            .get_execution_order()?
+            .iter()
+            .filter_map(|id| self.dag.get_task(id).cloned())
+            .collect())
+    }
+
+    pub fn schedule_report(&self) -> Result<Vec<TaskSchedule>, BetError> {
+        self.dag.schedule_report()
+    }
+
+    pub fn display_tree(&self) -> Result<String, BetError> {
+        self.dag.display_tree()
+    }
+
+    // Steps affected if `id` slips
+    pub fn descendants(&self, id: &str) -> Result<Vec<String>, BetError> {
+        self.dag.descendants(id)
+    }
+
+    pub fn ancestors(&self, id: &str) -> Result<Vec<String>, BetError> {
+        self.dag.ancestors(id)
+    }
+
     pub fn display(&self) -> String {
         self.dag.display()
     }
 }
 
+// Assembles a custom workflow step by step; problems surface at add_step/build
+// instead of when the execution order is first requested
+pub struct TradingWorkflowBuilder {
+    dag: OctoTree,
+}
+
+impl Default for TradingWorkflowBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TradingWorkflowBuilder {
+    pub fn new() -> Self {
+        TradingWorkflowBuilder { dag: OctoTree::new() }
+    }
+
+    pub fn add_step(self, id: &str, name: &str, duration: u32, dependencies: &[&str]) -> Result<Self, BetError> {
+        self.add_task(Task::new(id, name, duration, dependencies))
+    }
+
+    pub fn add_task(mut self, task: Task) -> Result<Self, BetError> {
+        if self.dag.contains_task(&task.id) {
+            return Err(BetError::DuplicateTask(task.id));
+        }
+        self.dag.add_task(task);
+        Ok(self)
+    }
+
+    // Sorts eagerly so cycles and dangling dependencies are reported here
+    pub fn build(self) -> Result<TradingWorkflow, BetError> {
+        self.dag.topological_sort()?;
+        Ok(TradingWorkflow { dag: self.dag })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -81,7 +119,7 @@ mod tests {
         let order = workflow.get_execution_order().unwrap();
         
         // Verify we have the expected tasks
-        assert!(order.len() > 0);
+        assert!(!order.is_empty());
         assert!(order.contains(&"fetch_data".to_string()));
         assert!(order.contains(&"execute_trades".to_string()));
     }
@@ -124,4 +162,62 @@ mod tests {
         let display = workflow.display();
         assert!(display.contains("5 tasks"));
     }
+
+    #[test]
+    fn test_default_workflow_schedule_report() {
+        let report = TradingWorkflow::new().schedule_report().unwrap();
+        let rows: Vec<(&str, u32, u32, u32, u32, u32)> = report
+            .iter()
+            .map(|t| (t.id.as_str(), t.earliest_start, t.earliest_finish, t.latest_start, t.latest_finish, t.slack))
+            .collect();
+        // A straight chain: every task is critical
+        assert_eq!(
+            rows,
+            vec![
+                ("fetch_data", 0, 2, 0, 2, 0),
+                ("calculate_indicators", 2, 5, 2, 5, 0),
+                ("generate_signals", 5, 7, 5, 7, 0),
+                ("risk_check", 7, 8, 7, 8, 0),
+                ("execute_trades", 8, 10, 8, 10, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_builder_builds_valid_workflow() {
+        let workflow = TradingWorkflow::builder()
+            .add_step("fetch", "Fetch", 1, &[])
+            .and_then(|b| b.add_step("trade", "Trade", 2, &["fetch"]))
+            .and_then(|b| b.build())
+            .unwrap();
+        assert_eq!(workflow.get_execution_order().unwrap(), vec!["fetch", "trade"]);
+    }
+
+    #[test]
+    fn test_builder_rejects_cycle_at_build() {
+        let builder = TradingWorkflow::builder()
+            .add_step("fetch", "Fetch", 1, &["trade"])
+            .and_then(|b| b.add_step("trade", "Trade", 2, &["fetch"]))
+            .unwrap();
+        match builder.build() {
+            Err(BetError::Cycle(tasks)) => assert_eq!(tasks, vec!["fetch", "trade"]),
+            other => panic!("expected cycle error, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn test_builder_rejects_duplicate_id_immediately() {
+        let builder = TradingWorkflow::builder().add_step("fetch", "Fetch", 1, &[]).unwrap();
+        let err = builder.add_step("fetch", "Fetch again", 1, &[]).err();
+        assert_eq!(err, Some(BetError::DuplicateTask("fetch".to_string())));
+    }
+
+    #[test]
+    fn test_descendants_of_calculate_indicators() {
+        let workflow = TradingWorkflow::new();
+        let downstream = workflow.descendants("calculate_indicators").unwrap();
+        assert_eq!(downstream, vec!["execute_trades", "generate_signals", "risk_check"]);
+        assert_eq!(workflow.ancestors("calculate_indicators").unwrap(), vec!["fetch_data"]);
+        assert!(matches!(workflow.descendants("missing"), Err(BetError::UnknownTask(_))));
+    }
 }
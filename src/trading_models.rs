@@ -1,6 +1,8 @@
 // Trading Models
 // Core data structures for biotech trading
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +40,54 @@ impl Position {
     pub fn market_value(&self) -> f64 {
         self.current_price * self.quantity
     }
+
+    // Signed like quantity: negative for shorts
+    pub fn cost_basis(&self) -> f64 {
+        self.avg_price * self.quantity
+    }
+
+    // Percent gain on the entry price from the holder's side, so a short gains as
+    // the price falls. 0 when there is no entry price
+    pub fn return_pct(&self) -> f64 {
+        if self.avg_price == 0.0 {
+            return 0.0;
+        }
+        let pct = (self.current_price - self.avg_price) / self.avg_price * 100.0;
+        if self.quantity < 0.0 {
+            -pct
+        } else {
+            pct
+        }
+    }
+
+    // Merge a signed fill into the position, returning any P&L realized by
+    // reducing or flipping it. Adds average in; reductions keep avg_price.
+    pub fn apply_fill(&mut self, quantity: f64, price: f64) -> f64 {
+        if quantity == 0.0 {
+            return 0.0;
+        }
+        self.current_price = price;
+
+        if self.quantity == 0.0 || self.quantity.signum() == quantity.signum() {
+            let new_quantity = self.quantity + quantity;
+            self.avg_price = (self.avg_price * self.quantity + price * quantity) / new_quantity;
+            self.quantity = new_quantity;
+            return 0.0;
+        }
+
+        let closing = quantity.abs().min(self.quantity.abs());
+        let realized = closing * (price - self.avg_price) * self.quantity.signum();
+        let new_quantity = self.quantity + quantity;
+
+        if new_quantity == 0.0 {
+            self.avg_price = 0.0;
+        } else if new_quantity.signum() != self.quantity.signum() {
+            // Flipped through flat: the remainder was opened at this price
+            self.avg_price = price;
+        }
+        self.quantity = new_quantity;
+        realized
+    }
 }
 
 pub fn get_biotech_universe() -> Vec<BiotechSymbol> {
@@ -50,6 +100,47 @@ pub fn get_biotech_universe() -> Vec<BiotechSymbol> {
     ]
 }
 
+// Forgiving ticker lookup: case, surrounding whitespace and share-class separators
+// ("BRK.B", "brk/b", "BRK-B") don't matter, and aliases map onto canonical tickers
+pub struct SymbolTable {
+    symbols: Vec<BiotechSymbol>,
+    aliases: HashMap<String, String>,
+}
+
+impl Default for SymbolTable {
+    fn default() -> Self {
+        Self::new(get_biotech_universe())
+    }
+}
+
+impl SymbolTable {
+    pub fn new(symbols: Vec<BiotechSymbol>) -> Self {
+        SymbolTable {
+            symbols,
+            aliases: HashMap::new(),
+        }
+    }
+
+    pub fn add_alias(&mut self, alias: &str, canonical: &str) {
+        let canonical = self.normalize(canonical);
+        self.aliases.insert(canonical_form(alias), canonical);
+    }
+
+    pub fn normalize(&self, raw: &str) -> String {
+        let ticker = canonical_form(raw);
+        self.aliases.get(&ticker).cloned().unwrap_or(ticker)
+    }
+
+    pub fn resolve(&self, raw: &str) -> Option<&BiotechSymbol> {
+        let ticker = self.normalize(raw);
+        self.symbols.iter().find(|s| canonical_form(&s.ticker) == ticker)
+    }
+}
+
+fn canonical_form(raw: &str) -> String {
+    raw.trim().to_uppercase().replace(['.', '/'], "-")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -96,6 +187,76 @@ mod tests {
         assert_eq!(position.market_value(), 6000.0); // 60 * 100
     }
 
+    #[test]
+    fn test_position_cost_basis_and_return() {
+        let long = Position {
+            symbol: "TEST".to_string(),
+            quantity: 100.0,
+            avg_price: 50.0,
+            current_price: 60.0,
+        };
+        assert_eq!(long.cost_basis(), 5000.0);
+        assert_eq!(long.return_pct(), 20.0);
+
+        let short = Position { quantity: -100.0, ..long.clone() };
+        assert_eq!(short.cost_basis(), -5000.0);
+        assert_eq!(short.return_pct(), -20.0);
+
+        let unpriced = Position { avg_price: 0.0, ..long };
+        assert_eq!(unpriced.return_pct(), 0.0);
+    }
+
+    #[test]
+    fn test_position_apply_fill_adds_and_reduces() {
+        let mut position = Position {
+            symbol: "TEST".to_string(),
+            quantity: 0.0,
+            avg_price: 0.0,
+            current_price: 0.0,
+        };
+        assert_eq!(position.apply_fill(100.0, 10.0), 0.0);
+        assert_eq!(position.apply_fill(100.0, 20.0), 0.0);
+        assert_eq!(position.quantity, 200.0);
+        assert_eq!(position.avg_price, 15.0);
+
+        assert_eq!(position.apply_fill(-50.0, 25.0), 500.0);
+        assert_eq!(position.quantity, 150.0);
+        assert_eq!(position.avg_price, 15.0);
+    }
+
+    #[test]
+    fn test_position_apply_fill_flips_side() {
+        let mut position = Position {
+            symbol: "TEST".to_string(),
+            quantity: 100.0,
+            avg_price: 50.0,
+            current_price: 50.0,
+        };
+        assert_eq!(position.apply_fill(-150.0, 40.0), -1000.0);
+        assert_eq!(position.quantity, -50.0);
+        assert_eq!(position.avg_price, 40.0);
+
+        assert_eq!(position.apply_fill(50.0, 30.0), 500.0);
+        assert_eq!(position.quantity, 0.0);
+    }
+
+    #[test]
+    fn test_position_apply_fill_ignores_empty_fill() {
+        let mut position = Position {
+            symbol: "TEST".to_string(),
+            quantity: 0.0,
+            avg_price: 0.0,
+            current_price: 0.0,
+        };
+        assert_eq!(position.apply_fill(0.0, 10.0), 0.0);
+        assert_eq!(position.quantity, 0.0);
+        assert_eq!(position.avg_price, 0.0);
+
+        position.apply_fill(100.0, 10.0);
+        assert_eq!(position.apply_fill(0.0, 20.0), 0.0);
+        assert_eq!(position.avg_price, 10.0);
+    }
+
     #[test]
     fn test_get_biotech_universe() {
         let universe = get_biotech_universe();
@@ -103,4 +264,24 @@ mod tests {
         assert_eq!(universe[0].ticker, "BIIB");
         assert_eq!(universe[4].ticker, "AMGN");
     }
+
+    #[test]
+    fn test_symbol_table_case_and_whitespace() {
+        let table = SymbolTable::default();
+        assert_eq!(table.normalize("  gild "), "GILD");
+        assert_eq!(table.resolve("gild").unwrap().company_name, "Gilead Sciences");
+        assert_eq!(table.resolve("GILD ").unwrap().company_name, "Gilead Sciences");
+        assert!(table.resolve("ZZZZ").is_none());
+    }
+
+    #[test]
+    fn test_symbol_table_aliases_and_separators() {
+        let mut table = SymbolTable::new(vec![BiotechSymbol::new("BRK-B", "Berkshire Hathaway", "Conglomerate", 1.0)]);
+        assert_eq!(table.normalize("brk.b"), "BRK-B");
+        assert!(table.resolve("BRK/B").is_some());
+
+        table.add_alias("berkshire", "brk.b");
+        assert_eq!(table.normalize(" Berkshire"), "BRK-B");
+        assert_eq!(table.resolve("BERKSHIRE").unwrap().company_name, "Berkshire Hathaway");
+    }
 }
@@ -1,10 +1,16 @@
 // Trading System
 // Unified interface for all trading components
 
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
 use crate::cure_foundation::CureFoundation;
-use crate::market_data::MarketDataFeed;
+use crate::error::BetError;
+use crate::market_data::{MarketDataFeed, OHLCV};
+use crate::money::{format_money, MoneyFormat};
 use crate::pnl::PnLCalculator;
-use crate::signals::TradingSignal;
+use crate::signals::{detect_crossover, SignalType, TradingSignal};
+use crate::trading::{Blotter, IdGenerator, Order, OrderSide, OrderType, Trade};
 use crate::trading_models::{BiotechSymbol, Position};
 
 pub struct TradingSystem {
@@ -14,6 +20,17 @@ pub struct TradingSystem {
     pub positions: Vec<Position>,
     pub signals: Vec<TradingSignal>,
     pub cure_foundation: CureFoundation,
+    pub blotter: Blotter,
+    pub price_history: HashMap<String, Vec<f64>>,
+    // Kill switch: halt once equity falls this many percent below its peak; None disables it
+    pub max_drawdown_pct: Option<f64>,
+    pub money_format: MoneyFormat,
+    equity_curve: Vec<f64>,
+    // Highest equity since start or the last reset_halt; drawdown is measured from here
+    peak_equity: f64,
+    halted: bool,
+    order_ids: IdGenerator,
+    signal_ids: IdGenerator,
 }
 
 impl TradingSystem {
@@ -25,6 +42,15 @@ impl TradingSystem {
             positions: Vec::new(),
             signals: Vec::new(),
             cure_foundation: crate::cure_foundation::initialize_cure_foundation(),
+            blotter: Blotter::new(),
+            price_history: HashMap::new(),
+            max_drawdown_pct: None,
+            money_format: MoneyFormat::default(),
+            equity_curve: vec![initial_capital],
+            peak_equity: initial_capital,
+            halted: false,
+            order_ids: IdGenerator::default(),
+            signal_ids: IdGenerator::default(),
         }
     }
 
@@ -32,26 +58,215 @@ impl TradingSystem {
         self.positions.push(position);
     }
 
-    pub fn add_signal(&mut self, signal: TradingSignal) {
+    // Change the capital base in place; positions, fills, ids and the halt state stay as they are.
+    // The drawdown peak moves with it so a smaller base doesn't read as a loss
+    pub fn set_initial_capital(&mut self, amount: f64) {
+        self.peak_equity += amount - self.pnl_calc.initial_capital();
+        self.pnl_calc.set_initial_capital(amount);
+    }
+
+    // A new order stamped with the next order id
+    pub fn create_order(&mut self, symbol: &str, side: OrderSide, order_type: OrderType, quantity: f64) -> Order {
+        Order::new(symbol, side, order_type, quantity).with_id(self.order_ids.next_id())
+    }
+
+    // Capital plus realized and unrealized P&L
+    pub fn equity(&self) -> f64 {
+        self.pnl_calc.initial_capital() + self.pnl_calc.calculate_report(&self.positions).total_pnl
+    }
+
+    pub fn equity_curve(&self) -> &[f64] {
+        &self.equity_curve
+    }
+
+    // Append the current equity to the curve, tripping the kill switch on a breach
+    pub fn record_equity(&mut self) -> f64 {
+        let equity = self.equity();
+        self.equity_curve.push(equity);
+        self.peak_equity = self.peak_equity.max(equity);
+        if let Some(limit) = self.max_drawdown_pct {
+            let peak = self.peak_equity;
+            if peak > 0.0 && (peak - equity) / peak * 100.0 > limit {
+                self.halted = true;
+            }
+        }
+        equity
+    }
+
+    // Stays true after a breach until reset_halt, even if equity recovers
+    pub fn trading_halted(&self) -> bool {
+        self.halted
+    }
+
+    // Manual re-enable; the drawdown peak restarts from current equity so the same
+    // loss doesn't immediately trip the switch again. The equity curve is kept
+    pub fn reset_halt(&mut self) {
+        self.halted = false;
+        self.peak_equity = self.equity();
+    }
+
+    // Execute an order at `price`, record the fill and fold it into positions.
+    // Ok(None) means the order didn't fill at this price
+    pub fn submit_order(&mut self, order: &mut Order, price: f64, timestamp: u64) -> Result<Option<Trade>, BetError> {
+        if self.halted {
+            return Err(BetError::Halted(format!(
+                "drawdown exceeded {}%, rejecting {} order",
+                self.max_drawdown_pct.unwrap_or(0.0),
+                order.symbol
+            )));
+        }
+        if order.id == 0 {
+            order.id = self.order_ids.next_id();
+        }
+        let Some(fill) = order.execute(price) else {
+            return Ok(None);
+        };
+        let trade = Trade {
+            id: self.blotter.next_id(),
+            symbol: fill.symbol.clone(),
+            side: order.side,
+            quantity: fill.quantity.abs(),
+            price,
+            timestamp,
+            signal_id: order.signal_id,
+        };
+        self.blotter.record(trade.clone())?;
+
+        match self.positions.iter_mut().position(|p| p.symbol == fill.symbol) {
+            Some(idx) => {
+                let realized = self.positions[idx].apply_fill(fill.quantity, price);
+                self.pnl_calc.add_realized_pnl(realized);
+                if self.positions[idx].quantity == 0.0 {
+                    self.positions.remove(idx);
+                }
+            }
+            None => self.positions.push(fill),
+        }
+
+        self.record_equity();
+        Ok(Some(trade))
+    }
+
+    pub fn ingest_price(&mut self, symbol: &str, price: f64) {
+        self.price_history.entry(symbol.to_string()).or_default().push(price);
+    }
+
+    // Signals for every symbol whose fast SMA crossed its slow SMA on the latest price
+    pub fn check_crossovers(&self, fast: usize, slow: usize) -> Vec<TradingSignal> {
+        let mut symbols: Vec<&String> = self.price_history.keys().collect();
+        symbols.sort();
+
+        symbols
+            .into_iter()
+            .filter_map(|symbol| {
+                let signal_type = detect_crossover(&self.price_history[symbol], fast, slow)?;
+                let direction = if signal_type == SignalType::Buy { "above" } else { "below" };
+                let reason = format!("SMA({}) crossed {} SMA({})", fast, direction, slow);
+                Some(TradingSignal::new(signal_type, symbol, 1.0, &reason))
+            })
+            .collect()
+    }
+
+    // Stamps an id on unnumbered signals and returns it, for attributing orders
+    pub fn add_signal(&mut self, mut signal: TradingSignal) -> u64 {
+        if signal.id == 0 {
+            signal.id = self.signal_ids.next_id();
+        } else {
+            self.signal_ids.observe(signal.id);
+        }
+        let id = signal.id;
         self.signals.push(signal);
+        id
     }
 
     pub fn get_portfolio_value(&self) -> f64 {
         self.positions.iter().map(|p| p.market_value()).sum()
     }
 
+    // Signed sum of market values: longs add, shorts subtract
+    pub fn net_exposure(&self) -> f64 {
+        self.get_portfolio_value()
+    }
+
+    pub fn gross_exposure(&self) -> f64 {
+        self.positions.iter().map(|p| p.market_value().abs()).sum()
+    }
+
+    // (long notional, short notional), both as positive amounts
+    pub fn long_short_breakdown(&self) -> (f64, f64) {
+        self.positions.iter().fold((0.0, 0.0), |(long, short), p| {
+            let value = p.market_value();
+            if value >= 0.0 {
+                (long + value, short)
+            } else {
+                (long, short - value)
+            }
+        })
+    }
+
+    // Deterministically nudge every position's price to simulate a market tick,
+    // recording each move as a one-tick bar in the feed. Returns the portfolio value
+    pub fn simulate_tick(&mut self, tick: u64) -> f64 {
+        for (i, position) in self.positions.iter_mut().enumerate() {
+            let pct = 0.01 * (tick as f64 * 0.7 + i as f64 * 1.3).sin();
+            let open = position.current_price;
+            position.current_price = (open * (1.0 + pct)).max(0.01);
+            let close = position.current_price;
+            self.market_feed.add_bar(
+                &position.symbol,
+                OHLCV {
+                    open,
+                    high: open.max(close),
+                    low: open.min(close),
+                    close,
+                    volume: 0,
+                    timestamp: tick,
+                },
+            );
+        }
+        self.record_equity();
+        self.get_portfolio_value()
+    }
+
     pub fn display_summary(&self) -> String {
         format!(
-            "Trading System:\n  Symbols: {}\n  Positions: {}\n  Signals: {}\n  Portfolio Value: ${:.2}\n  {}",
+            "Trading System:\n  Symbols: {}\n  Positions: {}\n  Signals: {}\n  Portfolio Value: {}\n  {}",
             self.biotech_symbols.len(),
             self.positions.len(),
             self.signals.len(),
-            self.get_portfolio_value(),
-            self.cure_foundation.display()
+            format_money(self.get_portfolio_value(), &self.money_format),
+            self.cure_foundation.display_with(&self.money_format)
         )
     }
 }
 
+// Handle to one TradingSystem shared across threads. Every method takes the lock
+// for just that call; use lock() to hold it across several steps
+pub struct SharedTradingSystem(Arc<Mutex<TradingSystem>>);
+
+impl SharedTradingSystem {
+    pub fn new(system: TradingSystem) -> Self {
+        SharedTradingSystem(Arc::new(Mutex::new(system)))
+    }
+
+    pub fn clone_handle(&self) -> SharedTradingSystem {
+        SharedTradingSystem(Arc::clone(&self.0))
+    }
+
+    // A panic on another thread doesn't lock everyone else out of the book
+    pub fn lock(&self) -> MutexGuard<'_, TradingSystem> {
+        self.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn add_position(&self, position: Position) {
+        self.lock().add_position(position);
+    }
+
+    pub fn get_portfolio_value(&self) -> f64 {
+        self.lock().get_portfolio_value()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -106,6 +321,64 @@ mod tests {
         assert_eq!(system.get_portfolio_value(), 11000.0); // 100*55 + 50*110
     }
 
+    #[test]
+    fn test_trading_system_simulate_tick() {
+        let mut system = TradingSystem::new(1_000_000.0);
+        system.add_position(Position {
+            symbol: "TEST1".to_string(),
+            quantity: 100.0,
+            avg_price: 50.0,
+            current_price: 50.0,
+        });
+        system.add_position(Position {
+            symbol: "TEST2".to_string(),
+            quantity: 10.0,
+            avg_price: 200.0,
+            current_price: 200.0,
+        });
+
+        let before = system.get_portfolio_value();
+        let value = system.simulate_tick(1);
+        assert_eq!(value, system.get_portfolio_value());
+        assert_ne!(value, before);
+        assert_ne!(system.positions[0].current_price, 50.0);
+
+        let mut replay = TradingSystem::new(1_000_000.0);
+        replay.add_position(Position {
+            symbol: "TEST1".to_string(),
+            quantity: 100.0,
+            avg_price: 50.0,
+            current_price: 50.0,
+        });
+        replay.add_position(Position {
+            symbol: "TEST2".to_string(),
+            quantity: 10.0,
+            avg_price: 200.0,
+            current_price: 200.0,
+        });
+        assert_eq!(replay.simulate_tick(1), value);
+    }
+
+    #[test]
+    fn test_trading_system_submit_order() {
+        let mut system = TradingSystem::new(1_000_000.0);
+        let mut buy = Order::new("BIIB", OrderSide::Buy, OrderType::Market, 100.0);
+        let trade = system.submit_order(&mut buy, 50.0, 1).unwrap().unwrap();
+        assert_eq!(trade.id, 1);
+        assert_eq!(system.positions.len(), 1);
+
+        let mut limit = Order::new("BIIB", OrderSide::Sell, OrderType::Limit(60.0), 100.0);
+        assert_eq!(system.submit_order(&mut limit, 55.0, 2), Ok(None));
+
+        let mut sell = Order::new("BIIB", OrderSide::Sell, OrderType::Market, 100.0);
+        system.submit_order(&mut sell, 55.0, 3).unwrap().unwrap();
+        assert!(system.positions.is_empty());
+        assert_eq!(system.pnl_calc.calculate_report(&[]).realized_pnl, 500.0);
+
+        assert_eq!(system.blotter.for_symbol("BIIB").len(), 2);
+        assert_eq!(system.blotter.total_volume(), 200.0);
+    }
+
     #[test]
     fn test_trading_system_display_summary() {
         let system = TradingSystem::new(1_000_000.0);
@@ -113,5 +386,167 @@ mod tests {
         assert!(summary.contains("Trading System"));
         assert!(summary.contains("Symbols: 5"));
         assert!(summary.contains("CURE Foundation"));
+        assert!(summary.contains("Portfolio Value: $0.00"));
+    }
+
+    #[test]
+    fn test_display_summary_uses_money_format() {
+        let mut system = TradingSystem::new(1_000_000.0);
+        system.add_position(Position {
+            symbol: "TEST".to_string(),
+            quantity: 1_000.0,
+            avg_price: 12.0,
+            current_price: 12.5,
+        });
+        assert!(system.display_summary().contains("Portfolio Value: $12500.00"));
+
+        system.money_format = MoneyFormat::new("€", 0, true);
+        let summary = system.display_summary();
+        assert!(summary.contains("Portfolio Value: €12,500"));
+        assert!(summary.contains("€18M total funding"));
+    }
+
+    #[test]
+    fn test_check_crossovers_emits_buy() {
+        let mut system = TradingSystem::new(1_000_000.0);
+        for price in [10.0, 10.0, 10.0, 10.0] {
+            system.ingest_price("MRNA", price);
+            system.ingest_price("BNTX", price);
+        }
+        assert!(system.check_crossovers(2, 4).is_empty());
+
+        system.ingest_price("MRNA", 11.0);
+        system.ingest_price("BNTX", 10.0);
+        let signals = system.check_crossovers(2, 4);
+        assert_eq!(signals.len(), 1);
+        assert_eq!(signals[0].symbol, "MRNA");
+        assert_eq!(signals[0].signal_type, SignalType::Buy);
+        assert!(signals[0].reason.contains("above"));
+    }
+
+    #[test]
+    fn test_drawdown_kill_switch() {
+        let mut system = TradingSystem::new(100_000.0);
+        system.max_drawdown_pct = Some(10.0);
+        let mut buy = Order::new("MRNA", OrderSide::Buy, OrderType::Market, 1_000.0);
+        system.submit_order(&mut buy, 100.0, 1).unwrap().unwrap();
+
+        // Down 8%: within the limit
+        system.positions[0].current_price = 92.0;
+        system.record_equity();
+        assert!(!system.trading_halted());
+
+        // Down 12% from the 100k peak
+        system.positions[0].current_price = 88.0;
+        assert_eq!(system.record_equity(), 88_000.0);
+        assert!(system.trading_halted());
+
+        let mut more = Order::new("MRNA", OrderSide::Buy, OrderType::Market, 10.0);
+        let err = system.submit_order(&mut more, 88.0, 2).unwrap_err();
+        assert!(matches!(err, BetError::Halted(_)));
+        assert!(err.to_string().starts_with("Trading halted"));
+        assert!(more.is_open());
+        assert_eq!(system.blotter.trades().len(), 1);
+
+        let history = system.equity_curve().len();
+        system.reset_halt();
+        assert!(!system.trading_halted());
+        assert_eq!(system.equity_curve().len(), history);
+        assert!(system.submit_order(&mut more, 88.0, 3).unwrap().is_some());
+        assert_eq!(system.equity_curve().len(), history + 1);
+
+        // Measured from the reset point, a further 5% loss stays within the limit
+        system.positions[0].current_price = 83.6;
+        system.record_equity();
+        assert!(!system.trading_halted());
+    }
+
+    #[test]
+    fn test_lower_capital_is_not_a_drawdown() {
+        let mut system = TradingSystem::new(100_000.0);
+        system.max_drawdown_pct = Some(10.0);
+        system.set_initial_capital(50_000.0);
+        system.record_equity();
+        assert!(!system.trading_halted());
+    }
+
+    #[test]
+    fn test_orders_stamped_with_increasing_ids() {
+        let mut system = TradingSystem::new(1_000_000.0);
+        let first = system.create_order("BIIB", OrderSide::Buy, OrderType::Market, 10.0);
+        let second = system.create_order("BIIB", OrderSide::Buy, OrderType::Market, 10.0);
+        assert!(first.id > 0 && second.id > first.id);
+
+        // Unstamped orders get an id when submitted
+        let mut manual = Order::new("BIIB", OrderSide::Sell, OrderType::Market, 5.0);
+        system.submit_order(&mut manual, 50.0, 1).unwrap();
+        assert!(manual.id > second.id);
+    }
+
+    #[test]
+    fn test_exposure_long_and_short() {
+        let mut system = TradingSystem::new(1_000_000.0);
+        system.add_position(Position {
+            symbol: "MRNA".to_string(),
+            quantity: 100.0,
+            avg_price: 100.0,
+            current_price: 120.0,
+        });
+        system.add_position(Position {
+            symbol: "BNTX".to_string(),
+            quantity: -50.0,
+            avg_price: 90.0,
+            current_price: 80.0,
+        });
+
+        assert_eq!(system.net_exposure(), 8_000.0);
+        assert_eq!(system.gross_exposure(), 16_000.0);
+        assert_eq!(system.long_short_breakdown(), (12_000.0, 4_000.0));
+    }
+
+    #[test]
+    fn test_signal_originated_trade_is_attributed() {
+        let mut system = TradingSystem::new(100_000.0);
+        let first = system.add_signal(TradingSignal::new(SignalType::Buy, "MRNA", 0.9, "Breakout"));
+        let second = system.add_signal(TradingSignal::new(SignalType::Sell, "BNTX", 0.6, "Fade"));
+        assert_eq!((first, second), (1, 2));
+        assert_eq!(system.signals[0].id, first);
+
+        let mut order = system
+            .create_order("MRNA", OrderSide::Buy, OrderType::Market, 10.0)
+            .with_signal_id(first);
+        let trade = system.submit_order(&mut order, 50.0, 1).unwrap().unwrap();
+        assert_eq!(trade.signal_id, Some(first));
+
+        let mut manual = system.create_order("MRNA", OrderSide::Buy, OrderType::Market, 5.0);
+        system.submit_order(&mut manual, 51.0, 2).unwrap();
+
+        let attributed = system.blotter.trades_for_signal(first);
+        assert_eq!(attributed, vec![&trade]);
+        assert!(system.blotter.trades_for_signal(second).is_empty());
+    }
+
+    #[test]
+    fn test_shared_trading_system_across_threads() {
+        let shared = SharedTradingSystem::new(TradingSystem::new(100_000.0));
+        let workers: Vec<_> = (0..8)
+            .map(|i| {
+                let handle = shared.clone_handle();
+                std::thread::spawn(move || {
+                    handle.add_position(Position {
+                        symbol: format!("SYM{}", i),
+                        quantity: 10.0,
+                        avg_price: 50.0,
+                        current_price: 55.0,
+                    });
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        assert_eq!(shared.lock().positions.len(), 8);
+        assert_eq!(shared.get_portfolio_value(), 8.0 * 10.0 * 55.0);
     }
 }
@@ -3,13 +3,20 @@
 
 use bet_architecture::{
     adag::{OctoTree, Task},
+    backtest::Backtester,
+    cycle::{run_cycle, StrategyConfig},
+    market_data::OHLCV,
+    momentum::{calculate_macd, calculate_rsi},
     monad_lambda::{demonstrate_monad_system, MonadLaws, Plumber},
+    rng::Rng,
+    simulation::simulate_gbm,
     storm::{Bolt, EdisonBolt, KeyBounceBolt, PolymathBolt, RandomizeKeysBolt, StormTopology, SumBolt, WordCountBolt},
     swin_transformer::SwinTransformer,
+    trading::OrderSide,
     trading_dag::TradingWorkflow,
     trading_system::TradingSystem,
     trading_models::Position,
-    signals::{TradingSignal, SignalType},
+    signals::{generate_signals, TradingSignal, SignalType},
 };
 
 #[test]
@@ -20,7 +27,7 @@ fn test_trading_system_integration() {
     // Verify initial state
     assert_eq!(system.positions.len(), 0);
     assert_eq!(system.signals.len(), 0);
-    assert!(system.biotech_symbols.len() > 0);
+    assert!(!system.biotech_symbols.is_empty());
     
     // Add a position
     let position = Position {
@@ -148,26 +155,11 @@ fn test_adag_critical_path() {
     // Test A-DAG critical path analysis
     let mut dag = OctoTree::new();
     
-    dag.add_task(Task {
-        id: "A".to_string(),
-        name: "Task A".to_string(),
-        duration: 5,
-        dependencies: vec![],
-    });
+    dag.add_task(Task::new("A", "Task A", 5, &[]));
     
-    dag.add_task(Task {
-        id: "B".to_string(),
-        name: "Task B".to_string(),
-        duration: 10,
-        dependencies: vec!["A".to_string()],
-    });
+    dag.add_task(Task::new("B", "Task B", 10, &["A"]));
     
-    dag.add_task(Task {
-        id: "C".to_string(),
-        name: "Task C".to_string(),
-        duration: 3,
-        dependencies: vec!["A".to_string()],
-    });
+    dag.add_task(Task::new("C", "Task C", 3, &["A"]));
     
     // Test topological sort
     let sorted = dag.topological_sort().expect("Should sort successfully");
@@ -185,24 +177,14 @@ fn test_adag_cycle_detection() {
     // Test that cycles are detected
     let mut dag = OctoTree::new();
     
-    dag.add_task(Task {
-        id: "A".to_string(),
-        name: "Task A".to_string(),
-        duration: 5,
-        dependencies: vec!["B".to_string()],
-    });
+    dag.add_task(Task::new("A", "Task A", 5, &["B"]));
     
-    dag.add_task(Task {
-        id: "B".to_string(),
-        name: "Task B".to_string(),
-        duration: 3,
-        dependencies: vec!["A".to_string()],
-    });
+    dag.add_task(Task::new("B", "Task B", 3, &["A"]));
     
     // This should detect a cycle
     let result = dag.topological_sort();
     assert!(result.is_err());
-    assert!(result.unwrap_err().contains("Cycle"));
+    assert!(result.unwrap_err().to_string().contains("Cycle"));
 }
 
 #[test]
@@ -517,8 +499,8 @@ fn test_key_bounce_state_management() {
     let mut key_bounce = KeyBounceBolt::new();
     
     // Test sequence: A, A, B, B, A
-    let inputs = vec!["A", "A", "B", "B", "A"];
-    let expected_accepts = vec![true, false, true, false, true];
+    let inputs = ["A", "A", "B", "B", "A"];
+    let expected_accepts = [true, false, true, false, true];
     
     for (input, should_accept) in inputs.iter().zip(expected_accepts.iter()) {
         let result = key_bounce.execute(input);
@@ -529,3 +511,98 @@ fn test_key_bounce_state_management() {
         }
     }
 }
+
+#[test]
+fn test_backtest_indicator_strategy_integration() {
+    // Oscillating series drives RSI/MACD through the signal generator into orders
+    let prices: Vec<f64> = (0..120)
+        .map(|i| 100.0 + (i as f64 * 0.3).sin() * 10.0 + i as f64 * 0.05)
+        .collect();
+
+    let backtester = Backtester::new("CURE", 100_000.0, 10.0);
+    let result = backtester.run(&prices, |history| {
+        let rsi = calculate_rsi(&history[history.len().saturating_sub(15)..], 14);
+        let (macd, _, _) = calculate_macd(history);
+        generate_signals(rsi, macd)
+    });
+
+    assert_eq!(result.equity_curve.len(), prices.len());
+    let last_equity = *result.equity_curve.last().unwrap();
+    assert!((last_equity - (100_000.0 + result.report.total_pnl)).abs() < 1e-6);
+    assert!(result.report.display().contains("P&L Report"));
+}
+
+#[test]
+fn test_monte_carlo_paths_through_backtester() {
+    // Stress the backtester across many seeded GBM paths
+    let backtester = Backtester::new("SIM", 100_000.0, 100.0);
+    let mut rng = Rng::new(42);
+    let mut final_equities = Vec::new();
+
+    for _ in 0..50 {
+        let path = simulate_gbm(100.0, 0.0002, 0.02, 252, &mut rng);
+        let rsi = calculate_rsi(&path[path.len() - 15..], 14);
+        assert!((0.0..=100.0).contains(&rsi));
+
+        let result = backtester.run(&path, |history| {
+            if history.len() == 1 {
+                SignalType::Buy
+            } else {
+                SignalType::Hold
+            }
+        });
+        let expected = 100_000.0 + (path[path.len() - 1] - path[0]) * 100.0;
+        let last = *result.equity_curve.last().unwrap();
+        assert!((last - expected).abs() < 1e-6);
+        final_equities.push(last);
+    }
+
+    // Different paths should produce a spread of outcomes
+    let min = final_equities.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = final_equities.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    assert!(max > min);
+}
+
+fn canned_bars(closes: impl Iterator<Item = f64>) -> Vec<OHLCV> {
+    closes
+        .enumerate()
+        .map(|(i, close)| OHLCV::new(close, close + 1.0, close - 1.0, close, 10_000, i as u64).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_run_cycle_two_symbols() {
+    use std::collections::HashMap;
+
+    let mut system = TradingSystem::new(1_000_000.0);
+    system.add_position(Position {
+        symbol: "BNTX".to_string(),
+        quantity: 100.0,
+        avg_price: 120.0,
+        current_price: 120.0,
+    });
+
+    // MRNA: strong rally then a pullback (oversold RSI, positive MACD) -> buy
+    // BNTX: steady decline then a bounce (overbought RSI, negative MACD) -> sell
+    let mrna = canned_bars((0..54).map(|i| if i < 40 { 100.0 + 2.0 * i as f64 } else { 178.0 - 0.5 * (i - 39) as f64 }));
+    let bntx = canned_bars((0..54).map(|i| if i < 40 { 200.0 - 2.0 * i as f64 } else { 122.0 + 0.5 * (i - 39) as f64 }));
+    let bars = HashMap::from([("MRNA".to_string(), mrna), ("BNTX".to_string(), bntx)]);
+
+    let trades = run_cycle(&mut system, &bars, &StrategyConfig::default());
+    assert_eq!(trades.len(), 2);
+    // Symbols run in sorted order
+    assert_eq!(trades[0].symbol, "BNTX");
+    assert_eq!(trades[0].side, OrderSide::Sell);
+    assert_eq!(trades[0].price, 129.0);
+    assert_eq!(trades[1].symbol, "MRNA");
+    assert_eq!(trades[1].side, OrderSide::Buy);
+    assert_eq!(trades[1].price, 171.0);
+
+    // The BNTX long was closed out at a 9 point gain; MRNA is now held
+    assert_eq!(system.positions.len(), 1);
+    assert_eq!(system.positions[0].symbol, "MRNA");
+    assert_eq!(system.pnl_calc.calculate_report(&[]).realized_pnl, 900.0);
+    assert_eq!(system.signals.len(), 2);
+    assert_eq!(system.blotter.trades().len(), 2);
+}
+